@@ -0,0 +1,149 @@
+use super::{json_events, AgentAdapter, AgentInvocation, AgentOutput};
+use crate::eval::TokenUsage;
+use serde_json::Value;
+
+/// Adapter for the Claude Code CLI
+pub struct ClaudeCodeAdapter;
+
+impl AgentAdapter for ClaudeCodeAdapter {
+    fn name(&self) -> &str {
+        "claude_code"
+    }
+
+    fn install_command(&self) -> &str {
+        "npm install -g @anthropic-ai/claude-code"
+    }
+
+    fn required_env_vars(&self, _provider: &str) -> Vec<String> {
+        vec!["ANTHROPIC_API_KEY".to_string()]
+    }
+
+    fn invocation(&self, invocation: &AgentInvocation) -> Vec<String> {
+        // --max-turns caps the agent loop, --dangerously-skip-permissions and
+        // -p (print mode) make the run non-interactive. stream-json (which
+        // requires --verbose) keeps the transcript in the logs and ends each
+        // invocation with a `result` event carrying turn count and usage.
+        vec![
+            "claude".to_string(),
+            "--model".to_string(),
+            invocation.model.clone(),
+            "--max-turns".to_string(),
+            invocation.max_turns.to_string(),
+            "--output-format".to_string(),
+            "stream-json".to_string(),
+            "--verbose".to_string(),
+            "--dangerously-skip-permissions".to_string(),
+            "-p".to_string(),
+            invocation.prompt.clone(),
+        ]
+    }
+
+    fn parse_output(&self, output: &str) -> AgentOutput {
+        let mut parsed = AgentOutput::default();
+
+        // Each invocation (one per ralph-loop iteration) ends with its own
+        // result event, so totals are summed across all of them
+        for event in json_events(output, "result") {
+            if let Some(turns) = event.get("num_turns").and_then(Value::as_u64) {
+                *parsed.turns_used.get_or_insert(0) += turns as u32;
+            }
+
+            let cost = event.get("total_cost_usd").and_then(Value::as_f64);
+            parsed.cost_reported |= cost.is_some();
+
+            let usage = event.get("usage");
+            let tokens = |key: &str| {
+                usage
+                    .and_then(|u| u.get(key))
+                    .and_then(Value::as_u64)
+                    .unwrap_or(0)
+            };
+            parsed
+                .token_usage
+                .get_or_insert_with(TokenUsage::default)
+                .add(&TokenUsage {
+                    input_tokens: tokens("input_tokens"),
+                    output_tokens: tokens("output_tokens"),
+                    cache_read_input_tokens: tokens("cache_read_input_tokens"),
+                    cache_creation_input_tokens: tokens("cache_creation_input_tokens"),
+                    total_cost_usd: cost.unwrap_or(0.0),
+                });
+        }
+
+        parsed
+    }
+
+    fn hit_max_turns(&self, output: &str) -> bool {
+        output.contains("Reached max turns")
+            || json_events(output, "result").any(|event| {
+                event.get("subtype").and_then(Value::as_str) == Some("error_max_turns")
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invocation() {
+        let args = ClaudeCodeAdapter.invocation(&AgentInvocation {
+            model: "claude-sonnet-4-20250514".to_string(),
+            provider: "anthropic".to_string(),
+            max_turns: 10,
+            prompt: "Say hi".to_string(),
+            prompt_file: "prompt.txt".into(),
+        });
+        assert_eq!(args[0], "claude");
+        assert!(args.windows(2).any(|w| w == ["--max-turns", "10"]));
+        assert_eq!(args.last().unwrap(), "Say hi");
+    }
+
+    #[test]
+    fn test_hit_max_turns() {
+        assert!(ClaudeCodeAdapter.hit_max_turns("Error: Reached max turns (10)"));
+        assert!(ClaudeCodeAdapter
+            .hit_max_turns(r#"{"type":"result","subtype":"error_max_turns","num_turns":10}"#));
+        assert!(!ClaudeCodeAdapter.hit_max_turns("Done"));
+    }
+
+    #[test]
+    fn test_parse_stream_json_output() {
+        let output = r#"{"type":"system","subtype":"init","model":"claude-sonnet-4-20250514"}
+{"type":"assistant","message":{"content":[{"type":"text","text":"Done"}]}}
+{"type":"result","subtype":"success","num_turns":4,"total_cost_usd":0.05,"usage":{"input_tokens":100,"output_tokens":50,"cache_read_input_tokens":1000,"cache_creation_input_tokens":200}}
+TEST_OUTPUT_START
+test result: ok. 8 passed; 0 failed
+"#;
+
+        let parsed = ClaudeCodeAdapter.parse_output(output);
+        assert_eq!(parsed.turns_used, Some(4));
+        let usage = parsed.token_usage.unwrap();
+        assert_eq!(usage.input_tokens, 100);
+        assert_eq!(usage.output_tokens, 50);
+        assert_eq!(usage.cache_read_input_tokens, 1000);
+        assert_eq!(usage.cache_creation_input_tokens, 200);
+        assert!((usage.total_cost_usd - 0.05).abs() < 1e-9);
+        assert!(parsed.cost_reported);
+    }
+
+    #[test]
+    fn test_parse_output_sums_iterations() {
+        let output = r#"{"type":"result","num_turns":3,"total_cost_usd":0.5,"usage":{"input_tokens":10,"output_tokens":5}}
+{"type":"result","num_turns":2,"total_cost_usd":0.25,"usage":{"input_tokens":20,"output_tokens":7}}"#;
+
+        let parsed = ClaudeCodeAdapter.parse_output(output);
+        assert_eq!(parsed.turns_used, Some(5));
+        let usage = parsed.token_usage.unwrap();
+        assert_eq!(usage.input_tokens, 30);
+        assert_eq!(usage.output_tokens, 12);
+        assert!((usage.total_cost_usd - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_plain_output() {
+        let parsed = ClaudeCodeAdapter.parse_output("I implemented the functions.");
+        assert!(parsed.turns_used.is_none());
+        assert!(parsed.token_usage.is_none());
+    }
+}
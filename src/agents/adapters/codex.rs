@@ -0,0 +1,97 @@
+use super::{json_events, AgentAdapter, AgentInvocation, AgentOutput};
+use crate::eval::TokenUsage;
+use serde_json::Value;
+
+/// Adapter for the OpenAI Codex CLI
+pub struct CodexAdapter;
+
+impl AgentAdapter for CodexAdapter {
+    fn name(&self) -> &str {
+        "codex"
+    }
+
+    fn install_command(&self) -> &str {
+        "npm install -g @openai/codex"
+    }
+
+    fn required_env_vars(&self, _provider: &str) -> Vec<String> {
+        vec!["OPENAI_API_KEY".to_string()]
+    }
+
+    fn invocation(&self, invocation: &AgentInvocation) -> Vec<String> {
+        // `exec` is the non-interactive mode, --full-auto lets it edit files and
+        // run commands, --json emits JSONL events including per-turn usage.
+        // Codex has no turn cap (see `supports_max_turns`).
+        vec![
+            "codex".to_string(),
+            "exec".to_string(),
+            "--json".to_string(),
+            "--model".to_string(),
+            invocation.model.clone(),
+            "--full-auto".to_string(),
+            invocation.prompt.clone(),
+        ]
+    }
+
+    fn parse_output(&self, output: &str) -> AgentOutput {
+        let mut parsed = AgentOutput::default();
+
+        for event in json_events(output, "turn.completed") {
+            *parsed.turns_used.get_or_insert(0) += 1;
+            let Some(usage) = event.get("usage") else {
+                continue;
+            };
+            let tokens = |key: &str| usage.get(key).and_then(Value::as_u64).unwrap_or(0);
+
+            // OpenAI counts cached tokens as part of input_tokens; split them
+            // out so they line up with the cache fields of other tools
+            let cached = tokens("cached_input_tokens");
+            parsed
+                .token_usage
+                .get_or_insert_with(TokenUsage::default)
+                .add(&TokenUsage {
+                    input_tokens: tokens("input_tokens").saturating_sub(cached),
+                    output_tokens: tokens("output_tokens"),
+                    cache_read_input_tokens: cached,
+                    cache_creation_input_tokens: 0,
+                    total_cost_usd: 0.0,
+                });
+        }
+
+        parsed
+    }
+
+    fn supports_max_turns(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_exec_json_output() {
+        let output = r#"{"type":"thread.started","thread_id":"abc"}
+{"type":"turn.started"}
+{"type":"item.completed","item":{"id":"item_0","type":"agent_message","text":"Done"}}
+{"type":"turn.completed","usage":{"input_tokens":1200,"cached_input_tokens":1000,"output_tokens":80}}
+{"type":"turn.completed","usage":{"input_tokens":300,"cached_input_tokens":0,"output_tokens":20}}
+"#;
+
+        let parsed = CodexAdapter.parse_output(output);
+        assert_eq!(parsed.turns_used, Some(2));
+        let usage = parsed.token_usage.unwrap();
+        assert_eq!(usage.input_tokens, 500);
+        assert_eq!(usage.cache_read_input_tokens, 1000);
+        assert_eq!(usage.output_tokens, 100);
+        assert!(!parsed.cost_reported);
+    }
+
+    #[test]
+    fn test_parse_output_without_usage() {
+        let parsed = CodexAdapter.parse_output("plain text output");
+        assert!(parsed.token_usage.is_none());
+        assert!(parsed.turns_used.is_none());
+    }
+}
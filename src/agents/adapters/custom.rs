@@ -0,0 +1,127 @@
+use super::{shell_quote, AgentAdapter, AgentInvocation, AgentOutput};
+use crate::agents::CustomAgentConfig;
+use std::sync::Arc;
+
+/// Adapter for an agent CLI defined in the eval config
+pub struct CustomAdapter {
+    config: CustomAgentConfig,
+    parser: Option<Arc<dyn AgentAdapter>>,
+}
+
+impl CustomAdapter {
+    pub fn new(config: CustomAgentConfig) -> Self {
+        let parser = config.output_parser.and_then(|tool| tool.adapter());
+        Self { config, parser }
+    }
+
+    /// Fill the placeholders in the command template
+    fn render_command(&self, invocation: &AgentInvocation) -> String {
+        self.config
+            .command
+            .replace("{model}", &shell_quote(&invocation.model))
+            .replace(
+                "{prompt_file}",
+                &shell_quote(&invocation.prompt_file.to_string_lossy()),
+            )
+            .replace("{max_turns}", &invocation.max_turns.to_string())
+    }
+}
+
+impl AgentAdapter for CustomAdapter {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn install_command(&self) -> &str {
+        self.config
+            .install
+            .as_deref()
+            .unwrap_or("echo 'No install command'")
+    }
+
+    fn required_env_vars(&self, _provider: &str) -> Vec<String> {
+        self.config.env.clone()
+    }
+
+    fn invocation(&self, invocation: &AgentInvocation) -> Vec<String> {
+        // The template is a shell command line, so hand it to bash as a whole
+        vec![
+            "bash".to_string(),
+            "-c".to_string(),
+            self.render_command(invocation),
+        ]
+    }
+
+    fn parse_output(&self, output: &str) -> AgentOutput {
+        self.parser
+            .as_ref()
+            .map(|parser| parser.parse_output(output))
+            .unwrap_or_default()
+    }
+
+    fn hit_max_turns(&self, output: &str) -> bool {
+        self.parser
+            .as_ref()
+            .is_some_and(|parser| parser.hit_max_turns(output))
+    }
+
+    fn supports_max_turns(&self) -> bool {
+        self.config.command.contains("{max_turns}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::AgentTool;
+
+    fn aider() -> CustomAgentConfig {
+        CustomAgentConfig {
+            name: "aider".to_string(),
+            install: None,
+            command: "aider --model {model} --message-file {prompt_file} --max-turns {max_turns}"
+                .to_string(),
+            env: vec![],
+            output_parser: None,
+        }
+    }
+
+    #[test]
+    fn test_render_invocation() {
+        let adapter = CustomAdapter::new(aider());
+        let argv = adapter.invocation(&AgentInvocation {
+            model: "gpt-5".to_string(),
+            provider: "openai".to_string(),
+            max_turns: 7,
+            prompt: "Fix it".to_string(),
+            prompt_file: "/tmp/my prompt.txt".into(),
+        });
+        assert_eq!(
+            argv,
+            vec![
+                "bash",
+                "-c",
+                "aider --model gpt-5 --message-file '/tmp/my prompt.txt' --max-turns 7"
+            ]
+        );
+        assert_eq!(adapter.install_command(), "echo 'No install command'");
+    }
+
+    #[test]
+    fn test_output_parser_delegates_to_builtin() {
+        let config = CustomAgentConfig {
+            output_parser: Some(AgentTool::ClaudeCode),
+            ..aider()
+        };
+        let output = r#"{"type":"result","subtype":"success","num_turns":3,"usage":{"input_tokens":10,"output_tokens":5}}"#;
+
+        assert_eq!(
+            CustomAdapter::new(config).parse_output(output).turns_used,
+            Some(3)
+        );
+        assert!(CustomAdapter::new(aider())
+            .parse_output(output)
+            .turns_used
+            .is_none());
+    }
+}
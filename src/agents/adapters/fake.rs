@@ -0,0 +1,105 @@
+use super::{shell_quote, AgentAdapter, AgentInvocation, AgentOutput, ClaudeCodeAdapter};
+use std::path::{Path, PathBuf};
+
+/// Scripted agent that replays a fixture directory instead of calling a model
+///
+/// Files under `files/` are copied over the workspace, `*.patch` files are
+/// applied in name order (patches that are already applied are skipped, so
+/// ralph-loop iterations are idempotent) and `usage.json` is printed as the
+/// agent's output, in Claude Code's `result` event format.
+pub struct FakeAdapter {
+    fixtures: PathBuf,
+}
+
+impl FakeAdapter {
+    pub fn new(fixtures: &Path) -> Self {
+        // The agent runs inside the workspace, so relative paths would break
+        let fixtures = std::path::absolute(fixtures).unwrap_or_else(|_| fixtures.to_path_buf());
+        Self { fixtures }
+    }
+}
+
+impl AgentAdapter for FakeAdapter {
+    fn name(&self) -> &str {
+        "fake"
+    }
+
+    fn install_command(&self) -> &str {
+        "echo 'Fake agent needs no install'"
+    }
+
+    fn required_env_vars(&self, _provider: &str) -> Vec<String> {
+        vec![]
+    }
+
+    fn invocation(&self, _invocation: &AgentInvocation) -> Vec<String> {
+        let script = format!(
+            r#"set -e
+fixtures={fixtures}
+if [ -d "$fixtures/files" ]; then cp -R "$fixtures/files/." .; fi
+for patch in "$fixtures"/*.patch; do
+    [ -e "$patch" ] || continue
+    git apply --reverse --check "$patch" 2>/dev/null || git apply "$patch"
+done
+if [ -f "$fixtures/usage.json" ]; then cat "$fixtures/usage.json"; fi"#,
+            fixtures = shell_quote(&self.fixtures.to_string_lossy())
+        );
+        vec!["bash".to_string(), "-c".to_string(), script]
+    }
+
+    fn parse_output(&self, output: &str) -> AgentOutput {
+        ClaudeCodeAdapter.parse_output(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn test_replays_fixtures() {
+        let fixtures = tempfile::tempdir().unwrap();
+        let workspace = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(fixtures.path().join("files/src")).unwrap();
+        std::fs::write(fixtures.path().join("files/src/lib.rs"), "// solved\n").unwrap();
+        std::fs::write(
+            fixtures.path().join("001-readme.patch"),
+            "--- a/README.md\n+++ b/README.md\n@@ -1 +1 @@\n-TODO\n+Done\n",
+        )
+        .unwrap();
+        std::fs::write(
+            fixtures.path().join("usage.json"),
+            r#"{"type":"result","num_turns":2,"usage":{"input_tokens":100,"output_tokens":20}}"#,
+        )
+        .unwrap();
+        std::fs::write(workspace.path().join("README.md"), "TODO\n").unwrap();
+
+        let adapter = FakeAdapter::new(fixtures.path());
+        let argv = adapter.invocation(&AgentInvocation {
+            model: "claude-sonnet-4-20250514".to_string(),
+            provider: "anthropic".to_string(),
+            max_turns: 10,
+            prompt: "Solve it".to_string(),
+            prompt_file: "prompt.txt".into(),
+        });
+
+        // Running twice checks that already-applied patches are skipped
+        for _ in 0..2 {
+            let output = Command::new(&argv[0])
+                .args(&argv[1..])
+                .current_dir(workspace.path())
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{:?}", output);
+
+            let parsed = adapter.parse_output(&String::from_utf8_lossy(&output.stdout));
+            assert_eq!(parsed.turns_used, Some(2));
+        }
+
+        let lib = std::fs::read_to_string(workspace.path().join("src/lib.rs")).unwrap();
+        assert_eq!(lib, "// solved\n");
+        let readme = std::fs::read_to_string(workspace.path().join("README.md")).unwrap();
+        assert_eq!(readme, "Done\n");
+    }
+}
@@ -0,0 +1,170 @@
+//! Agent adapters
+//!
+//! Everything that differs between agent CLIs (how to install them, how to
+//! invoke them non-interactively, which credentials they need and how to read
+//! their output) lives behind the [`AgentAdapter`] trait. Adding a new agent
+//! CLI means adding one module here and registering it in
+//! [`AdapterRegistry::with_builtin`].
+
+mod claude_code;
+mod codex;
+mod custom;
+mod fake;
+mod opencode;
+
+pub use claude_code::ClaudeCodeAdapter;
+pub use codex::CodexAdapter;
+pub use custom::CustomAdapter;
+pub use fake::FakeAdapter;
+pub use opencode::OpenCodeAdapter;
+
+use crate::eval::TokenUsage;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+
+/// Parameters for a single non-interactive agent invocation
+#[derive(Debug, Clone)]
+pub struct AgentInvocation {
+    /// Model string passed to the CLI
+    pub model: String,
+    /// Provider of the model (e.g. `anthropic`), for CLIs driving several
+    pub provider: String,
+    /// Maximum number of turns the agent may take
+    pub max_turns: u32,
+    /// The prompt text
+    pub prompt: String,
+    /// File containing the prompt text
+    pub prompt_file: PathBuf,
+}
+
+/// Information extracted from an agent's output
+#[derive(Debug, Clone, Default)]
+pub struct AgentOutput {
+    /// Number of turns the agent used
+    pub turns_used: Option<u32>,
+    /// Token usage reported by the agent
+    pub token_usage: Option<TokenUsage>,
+    /// Whether the agent reported a dollar cost (otherwise it is estimated
+    /// from the model's prices)
+    pub cost_reported: bool,
+}
+
+/// Behaviour specific to one agent CLI
+pub trait AgentAdapter: Send + Sync {
+    /// Name the adapter is registered under (the `tool` value in configs)
+    fn name(&self) -> &str;
+
+    /// Shell command that installs the agent CLI
+    fn install_command(&self) -> &str;
+
+    /// Environment variables that must contain credentials for the agent to
+    /// use a model from `provider`
+    fn required_env_vars(&self, provider: &str) -> Vec<String>;
+
+    /// Build the command line (program followed by its arguments) that runs
+    /// the agent non-interactively
+    fn invocation(&self, invocation: &AgentInvocation) -> Vec<String>;
+
+    /// Extract turn count and token usage from the agent's output
+    fn parse_output(&self, _output: &str) -> AgentOutput {
+        AgentOutput::default()
+    }
+
+    /// Whether the output shows the agent stopped because it ran out of turns
+    fn hit_max_turns(&self, _output: &str) -> bool {
+        false
+    }
+
+    /// Whether the CLI enforces `max_turns`. Turn budgets can't be set for
+    /// agents whose CLI has no turn limit.
+    fn supports_max_turns(&self) -> bool {
+        true
+    }
+}
+
+/// Agent adapters registered by name
+#[derive(Default)]
+pub struct AdapterRegistry {
+    adapters: BTreeMap<String, Arc<dyn AgentAdapter>>,
+}
+
+impl AdapterRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry containing all built-in adapters
+    pub fn with_builtin() -> Self {
+        let mut registry = Self::new();
+        registry.register(Arc::new(ClaudeCodeAdapter));
+        registry.register(Arc::new(CodexAdapter));
+        registry.register(Arc::new(OpenCodeAdapter));
+        registry
+    }
+
+    /// Register an adapter, replacing any adapter with the same name
+    pub fn register(&mut self, adapter: Arc<dyn AgentAdapter>) {
+        self.adapters.insert(adapter.name().to_string(), adapter);
+    }
+
+    /// Look up an adapter by name
+    pub fn get(&self, name: &str) -> Option<Arc<dyn AgentAdapter>> {
+        self.adapters.get(name).cloned()
+    }
+}
+
+/// The registry of built-in adapters
+pub fn builtin_adapters() -> &'static AdapterRegistry {
+    static REGISTRY: OnceLock<AdapterRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(AdapterRegistry::with_builtin)
+}
+
+/// The JSON events of type `event_type` in output emitting one object per
+/// line, skipping any other lines
+fn json_events<'a>(output: &'a str, event_type: &'a str) -> impl Iterator<Item = Value> + 'a {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('{'))
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(move |event| event.get("type").and_then(Value::as_str) == Some(event_type))
+}
+
+/// Quote a single argument for a POSIX shell, leaving simple words untouched
+pub fn shell_quote(arg: &str) -> String {
+    let is_simple = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=,@+%".contains(c));
+
+    if is_simple {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_registry() {
+        let registry = builtin_adapters();
+        assert!(registry.get("claude_code").is_some());
+        assert!(registry.get("codex").is_some());
+        assert!(registry.get("opencode").is_some());
+        assert!(registry.get("unknown").is_none());
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("--max-turns"), "--max-turns");
+        assert_eq!(shell_quote("hello world"), "'hello world'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+}
@@ -0,0 +1,136 @@
+use super::{json_events, AgentAdapter, AgentInvocation, AgentOutput};
+use crate::eval::TokenUsage;
+use serde_json::Value;
+
+/// Adapter for the OpenCode CLI
+pub struct OpenCodeAdapter;
+
+impl AgentAdapter for OpenCodeAdapter {
+    fn name(&self) -> &str {
+        "opencode"
+    }
+
+    fn install_command(&self) -> &str {
+        "npm install -g opencode-ai"
+    }
+
+    fn required_env_vars(&self, provider: &str) -> Vec<String> {
+        // Local providers such as Ollama need no key
+        let var = match provider {
+            "anthropic" => "ANTHROPIC_API_KEY",
+            "openai" => "OPENAI_API_KEY",
+            "google" => "GOOGLE_GENERATIVE_AI_API_KEY",
+            _ => return vec![],
+        };
+        vec![var.to_string()]
+    }
+
+    fn invocation(&self, invocation: &AgentInvocation) -> Vec<String> {
+        // `run` is the non-interactive mode; --format json emits one event
+        // per line, with a step_finish event (tokens and cost) per model call.
+        // OpenCode has no turn cap (see `supports_max_turns`), and names
+        // models `provider/model`; unknown models are passed through as-is.
+        let model = if invocation.provider.is_empty() {
+            invocation.model.clone()
+        } else {
+            format!("{}/{}", invocation.provider, invocation.model)
+        };
+        vec![
+            "opencode".to_string(),
+            "run".to_string(),
+            "--format".to_string(),
+            "json".to_string(),
+            "--model".to_string(),
+            model,
+            invocation.prompt.clone(),
+        ]
+    }
+
+    fn parse_output(&self, output: &str) -> AgentOutput {
+        let mut parsed = AgentOutput::default();
+
+        for event in json_events(output, "step_finish") {
+            *parsed.turns_used.get_or_insert(0) += 1;
+
+            let Some(part) = event.get("part") else {
+                continue;
+            };
+            let cost = part.get("cost").and_then(Value::as_f64);
+            parsed.cost_reported |= cost.is_some();
+
+            let tokens = part.get("tokens");
+            let count = |path: &[&str]| {
+                tokens
+                    .and_then(|t| path.iter().try_fold(t, |v, key| v.get(key)))
+                    .and_then(Value::as_u64)
+                    .unwrap_or(0)
+            };
+            parsed
+                .token_usage
+                .get_or_insert_with(TokenUsage::default)
+                .add(&TokenUsage {
+                    input_tokens: count(&["input"]),
+                    // Reasoning tokens are billed as output
+                    output_tokens: count(&["output"]) + count(&["reasoning"]),
+                    cache_read_input_tokens: count(&["cache", "read"]),
+                    cache_creation_input_tokens: count(&["cache", "write"]),
+                    total_cost_usd: cost.unwrap_or(0.0),
+                });
+        }
+
+        parsed
+    }
+
+    fn supports_max_turns(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invocation() {
+        let args = OpenCodeAdapter.invocation(&AgentInvocation {
+            model: "qwen2.5-coder:7b".to_string(),
+            provider: "ollama".to_string(),
+            max_turns: 10,
+            prompt: "Say hi".to_string(),
+            prompt_file: "prompt.txt".into(),
+        });
+        assert!(args
+            .windows(2)
+            .any(|w| w == ["--model", "ollama/qwen2.5-coder:7b"]));
+    }
+
+    #[test]
+    fn test_required_env_vars() {
+        let adapter = OpenCodeAdapter;
+        assert_eq!(
+            adapter.required_env_vars("anthropic"),
+            ["ANTHROPIC_API_KEY"]
+        );
+        assert_eq!(adapter.required_env_vars("openai"), ["OPENAI_API_KEY"]);
+        assert!(adapter.required_env_vars("ollama").is_empty());
+    }
+
+    #[test]
+    fn test_parse_json_output() {
+        let output = r#"{"type":"step_start","part":{"type":"step-start"}}
+{"type":"text","part":{"type":"text","text":"Implementing..."}}
+{"type":"step_finish","part":{"type":"step-finish","cost":0.01,"tokens":{"input":100,"output":40,"reasoning":10,"cache":{"read":500,"write":50}}}}
+{"type":"step_finish","part":{"type":"step-finish","cost":0.02,"tokens":{"input":200,"output":60,"reasoning":0,"cache":{"read":0,"write":0}}}}
+"#;
+
+        let parsed = OpenCodeAdapter.parse_output(output);
+        assert_eq!(parsed.turns_used, Some(2));
+        let usage = parsed.token_usage.unwrap();
+        assert_eq!(usage.input_tokens, 300);
+        assert_eq!(usage.output_tokens, 110);
+        assert_eq!(usage.cache_read_input_tokens, 500);
+        assert_eq!(usage.cache_creation_input_tokens, 50);
+        assert!((usage.total_cost_usd - 0.03).abs() < 1e-9);
+        assert!(parsed.cost_reported);
+    }
+}
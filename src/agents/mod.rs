@@ -1,3 +1,7 @@
+mod adapters;
+mod models;
 mod types;
 
+pub use adapters::*;
+pub use models::*;
 pub use types::*;
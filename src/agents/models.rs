@@ -0,0 +1,190 @@
+//! Model registry
+//!
+//! Models are data, not code: the built-in registry is embedded from
+//! `models.yaml` and can be extended or overridden at startup with a user
+//! file in the same format.
+
+use super::AgentTool;
+use crate::eval::TokenUsage;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+const BUILTIN_MODELS: &str = include_str!("models.yaml");
+
+/// A model that agents can be evaluated with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelInfo {
+    /// Identifier used in eval configs (e.g. `claude_opus_4_5`)
+    pub id: String,
+    /// Model provider (e.g. `anthropic`, `openai`)
+    pub provider: String,
+    /// Model string passed to the agent CLI
+    pub api_model: String,
+    /// Agent tools that can drive this model
+    pub tools: Vec<AgentTool>,
+    /// Context window in tokens
+    #[serde(default)]
+    pub context_window: Option<u64>,
+    /// List prices, used to compute cost when the agent reports none
+    #[serde(default)]
+    pub pricing: Option<ModelPricing>,
+}
+
+/// Model prices in USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub input: f64,
+    pub output: f64,
+    pub cache_read: f64,
+    pub cache_write: f64,
+}
+
+impl ModelPricing {
+    /// Cost in USD of the given token usage
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        (usage.input_tokens as f64 * self.input
+            + usage.output_tokens as f64 * self.output
+            + usage.cache_read_input_tokens as f64 * self.cache_read
+            + usage.cache_creation_input_tokens as f64 * self.cache_write)
+            / 1_000_000.0
+    }
+}
+
+/// Layout of a model registry file
+#[derive(Debug, Deserialize)]
+struct ModelRegistryFile {
+    models: Vec<ModelInfo>,
+}
+
+/// Known models by id
+#[derive(Debug, Clone, Default)]
+pub struct ModelRegistry {
+    models: BTreeMap<String, ModelInfo>,
+}
+
+impl ModelRegistry {
+    /// Create a registry containing the built-in models
+    pub fn with_builtin() -> Self {
+        let mut registry = Self::default();
+        registry
+            .extend_from_str(BUILTIN_MODELS)
+            .expect("built-in model registry is valid");
+        registry
+    }
+
+    /// Add the models from a registry file, replacing models with the same id
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let content = std::fs::read_to_string(path.as_ref()).context(format!(
+            "Failed to read model registry: {:?}",
+            path.as_ref()
+        ))?;
+        self.extend_from_str(&content).context(format!(
+            "Failed to parse model registry: {:?}",
+            path.as_ref()
+        ))
+    }
+
+    fn extend_from_str(&mut self, content: &str) -> Result<()> {
+        let file: ModelRegistryFile = serde_yaml::from_str(content)?;
+        for model in file.models {
+            self.register(model);
+        }
+        Ok(())
+    }
+
+    /// Register a model, replacing any model with the same id
+    pub fn register(&mut self, model: ModelInfo) {
+        self.models.insert(model.id.clone(), model);
+    }
+
+    /// Look up a model by id
+    pub fn get(&self, id: &str) -> Option<&ModelInfo> {
+        self.models.get(id)
+    }
+
+    /// Ids of all registered models
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.models.keys().map(String::as_str)
+    }
+}
+
+static REGISTRY: OnceLock<ModelRegistry> = OnceLock::new();
+
+/// Install the model registry used for the rest of the process. Must be called
+/// before any model is looked up; later calls fail.
+pub fn init_model_registry(registry: ModelRegistry) -> Result<()> {
+    REGISTRY
+        .set(registry)
+        .map_err(|_| anyhow::anyhow!("Model registry already initialized"))
+}
+
+/// The model registry (the built-in models unless one was installed at startup)
+pub fn model_registry() -> &'static ModelRegistry {
+    REGISTRY.get_or_init(ModelRegistry::with_builtin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_registry() {
+        let registry = ModelRegistry::with_builtin();
+        let opus = registry.get("claude_opus_4_5").unwrap();
+        assert_eq!(opus.api_model, "claude-opus-4-5-20251101");
+        assert!(opus.tools.contains(&AgentTool::ClaudeCode));
+        assert!(!opus.tools.contains(&AgentTool::Codex));
+        assert!(registry.get("unknown").is_none());
+    }
+
+    #[test]
+    fn test_user_registry_overrides_builtin() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("models.yaml");
+        std::fs::write(
+            &path,
+            r#"
+models:
+  - id: claude_opus_4_5
+    provider: anthropic
+    api_model: claude-opus-4-5-20260101
+    tools: [claude_code]
+  - id: gemini_3_pro
+    provider: google
+    api_model: gemini-3-pro
+    tools: [opencode]
+    context_window: 1000000
+    pricing: { input: 2.0, output: 12.0, cache_read: 0.2, cache_write: 2.0 }
+"#,
+        )
+        .unwrap();
+
+        let mut registry = ModelRegistry::with_builtin();
+        registry.load_file(&path).unwrap();
+        assert_eq!(
+            registry.get("claude_opus_4_5").unwrap().api_model,
+            "claude-opus-4-5-20260101"
+        );
+        assert_eq!(registry.get("gemini_3_pro").unwrap().provider, "google");
+        assert!(registry.get("claude_sonnet_4").is_some());
+    }
+
+    #[test]
+    fn test_pricing_cost() {
+        let usage = TokenUsage {
+            input_tokens: 1_000_000,
+            output_tokens: 100_000,
+            cache_read_input_tokens: 2_000_000,
+            ..Default::default()
+        };
+        let pricing = ModelRegistry::with_builtin()
+            .get("claude_sonnet_4")
+            .unwrap()
+            .pricing
+            .unwrap();
+        assert!((pricing.cost(&usage) - 5.1).abs() < 1e-9);
+    }
+}
@@ -1,8 +1,17 @@
+use super::adapters::{
+    builtin_adapters, AgentAdapter, AgentInvocation, AgentOutput, CustomAdapter, FakeAdapter,
+};
+use super::models::{model_registry, ModelInfo, ModelPricing};
+use anyhow::{Context, Result};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Supported agent CLI tools
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum AgentTool {
     /// Claude Code CLI
@@ -10,7 +19,13 @@ pub enum AgentTool {
     /// OpenAI Codex CLI
     Codex,
     /// OpenCode CLI
+    #[serde(alias = "opencode")]
     OpenCode,
+    /// Any other CLI, defined by the agent's `custom` section
+    Custom,
+    /// Scripted agent replaying edits from a fixture directory, for testing
+    /// the framework without API keys
+    Fake,
 }
 
 impl fmt::Display for AgentTool {
@@ -19,143 +34,409 @@ impl fmt::Display for AgentTool {
             AgentTool::ClaudeCode => write!(f, "claude-code"),
             AgentTool::Codex => write!(f, "codex"),
             AgentTool::OpenCode => write!(f, "opencode"),
+            AgentTool::Custom => write!(f, "custom"),
+            AgentTool::Fake => write!(f, "fake"),
         }
     }
 }
 
 impl AgentTool {
-    /// Get the CLI command name for this agent
-    pub fn cli_command(&self) -> &'static str {
+    /// Name of the adapter registered for this tool
+    pub fn name(&self) -> &'static str {
         match self {
-            AgentTool::ClaudeCode => "claude",
+            AgentTool::ClaudeCode => "claude_code",
             AgentTool::Codex => "codex",
             AgentTool::OpenCode => "opencode",
+            AgentTool::Custom => "custom",
+            AgentTool::Fake => "fake",
         }
     }
 
-    /// Get the required environment variable for API key
-    pub fn api_key_env_var(&self) -> &'static str {
-        match self {
-            AgentTool::ClaudeCode => "ANTHROPIC_API_KEY",
-            AgentTool::Codex => "OPENAI_API_KEY",
-            AgentTool::OpenCode => "OPENAI_API_KEY",
-        }
+    /// Get the built-in adapter implementing this tool's CLI behaviour (custom
+    /// and fake tools have none; their adapter comes from the agent config)
+    pub fn adapter(&self) -> Option<Arc<dyn AgentAdapter>> {
+        builtin_adapters().get(self.name())
     }
+}
 
-    /// Get the install command for this agent CLI
-    pub fn install_command(&self) -> &'static str {
-        match self {
-            AgentTool::ClaudeCode => "npm install -g @anthropic-ai/claude-code",
-            AgentTool::Codex => "npm install -g @openai/codex",
-            AgentTool::OpenCode => "npm install -g opencode",
-        }
+/// A model, identified by its id in the model registry (e.g. `claude_opus_4_5`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(transparent)]
+pub struct ModelVersion(String);
+
+impl ModelVersion {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
     }
-}
 
-/// Supported model versions
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum ModelVersion {
-    /// Claude Opus 4.5 (latest)
-    #[serde(rename = "claude_opus_4_5")]
-    ClaudeOpus45,
-    /// Claude Sonnet 4
-    #[serde(rename = "claude_sonnet_4")]
-    ClaudeSonnet4,
-    /// Claude Haiku 4.5
-    #[serde(rename = "claude_haiku_4_5")]
-    ClaudeHaiku45,
-    /// GPT-5.2 Extra High
-    #[serde(rename = "gpt_5_2_xhigh")]
-    Gpt52XHigh,
-    /// GPT-5.2 High
-    #[serde(rename = "gpt_5_2_high")]
-    Gpt52High,
-    /// GPT-5
-    #[serde(rename = "gpt_5")]
-    Gpt5,
-    /// o3 reasoning model
-    #[serde(rename = "o3")]
-    O3,
-    /// Qwen Coder 8B
-    #[serde(rename = "qwen_coder_8b")]
-    QwenCoder8b,
-    /// Custom model string
-    Custom(String),
+    /// The model's registry id
+    pub fn id(&self) -> &str {
+        &self.0
+    }
+
+    /// Registry entry for this model, if it is known
+    pub fn info(&self) -> Option<&'static ModelInfo> {
+        model_registry().get(&self.0)
+    }
+
+    /// Model string passed to the agent CLI
+    pub fn api_model(&self) -> &str {
+        self.info().map_or(&self.0, |info| &info.api_model)
+    }
+
+    /// Provider serving the model (empty if the model is unknown)
+    pub fn provider(&self) -> &str {
+        self.info().map_or("", |info| &info.provider)
+    }
+
+    /// List prices for the model, if known
+    pub fn pricing(&self) -> Option<ModelPricing> {
+        self.info().and_then(|info| info.pricing)
+    }
 }
 
 impl fmt::Display for ModelVersion {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            ModelVersion::ClaudeOpus45 => write!(f, "claude-opus-4-5-20251101"),
-            ModelVersion::ClaudeSonnet4 => write!(f, "claude-sonnet-4-20250514"),
-            ModelVersion::ClaudeHaiku45 => write!(f, "claude-haiku-4-5-20251001"),
-            ModelVersion::Gpt52XHigh => write!(f, "gpt-5.2-xhigh"),
-            ModelVersion::Gpt52High => write!(f, "gpt-5.2-high"),
-            ModelVersion::Gpt5 => write!(f, "gpt-5"),
-            ModelVersion::O3 => write!(f, "o3"),
-            ModelVersion::QwenCoder8b => write!(f, "qwen2.5-coder:7b"),
-            ModelVersion::Custom(s) => write!(f, "{}", s),
-        }
+        write!(f, "{}", self.api_model())
     }
 }
 
 /// An agent configuration combining tool and model
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub struct AgentConfig {
+    /// Agent CLI to run
     pub tool: AgentTool,
+    /// Model id from the model registry (e.g. `claude_opus_4_5`)
     pub model: ModelVersion,
-    /// Number of iterations for ralph-loop style execution
-    #[serde(default = "default_iterations")]
-    pub iterations: u32,
+    /// Maximum number of turns per agent invocation (default: 10). Only
+    /// for tools whose CLI enforces a turn limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iterations: Option<u32>,
+    /// Re-invoke the agent until the tests pass ("ralph loop")
+    #[serde(default)]
+    pub ralph_loop: Option<RalphLoopConfig>,
+    /// Definition of the CLI when `tool` is `custom`
+    #[serde(default)]
+    pub custom: Option<CustomAgentConfig>,
+    /// Fixtures to replay when `tool` is `fake`
+    #[serde(default)]
+    pub fake: Option<FakeAgentConfig>,
+    /// Environment variables for the agent's runs (overriding the prompt's)
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Number of runs per prompt for this agent, overriding
+    /// `settings.samples`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = 1))]
+    pub samples: Option<u32>,
+}
+
+/// Ralph-loop execution: run the agent, run the tests, and re-run the agent on
+/// the same workspace until the tests pass or the iterations are used up
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+pub struct RalphLoopConfig {
+    /// Maximum number of agent invocations
+    pub max_iterations: u32,
+    /// Turn budget for each invocation (defaults to the agent's `iterations`)
+    #[serde(default)]
+    pub turns_per_iteration: Option<u32>,
+}
+
+/// A config-defined agent CLI, for tools without a built-in adapter
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+pub struct CustomAgentConfig {
+    /// Name used in agent ids and reports (e.g. `aider`): letters, digits, `-`
+    /// and `_`
+    pub name: String,
+    /// Shell command that installs the CLI
+    #[serde(default)]
+    pub install: Option<String>,
+    /// Shell command that runs the agent non-interactively. `{model}`,
+    /// `{prompt_file}` and `{max_turns}` are replaced before it runs.
+    pub command: String,
+    /// Environment variables that must contain credentials for the agent
+    #[serde(default)]
+    pub env: Vec<String>,
+    /// Built-in tool whose output format the CLI shares, used to extract turns
+    /// and token usage
+    #[serde(default)]
+    pub output_parser: Option<AgentTool>,
+}
+
+/// A scripted agent for exercising the framework offline
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+pub struct FakeAgentConfig {
+    /// Fixture directory: `files/` is copied over the workspace, `*.patch`
+    /// files are applied in name order and `usage.json` (Claude Code `result`
+    /// events, one per line) is printed as the agent's output
+    pub fixtures: PathBuf,
 }
 
 fn default_iterations() -> u32 {
     10
 }
 
+/// FNV-1a hash of an environment, stable across builds (unlike `std`'s
+/// hasher) so agent ids stay the same between runs
+fn env_hash(env: &BTreeMap<String, String>) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for (key, value) in env {
+        for byte in [key.as_bytes(), b"=", value.as_bytes(), b"\0"].concat() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
 impl AgentConfig {
     pub fn new(tool: AgentTool, model: ModelVersion) -> Self {
         Self {
             tool,
             model,
-            iterations: default_iterations(),
+            iterations: None,
+            ralph_loop: None,
+            custom: None,
+            fake: None,
+            env: BTreeMap::new(),
+            samples: None,
         }
     }
 
+    /// Create an agent running a config-defined CLI
+    #[cfg(test)]
+    pub fn custom(custom: CustomAgentConfig, model: ModelVersion) -> Self {
+        Self {
+            custom: Some(custom),
+            ..Self::new(AgentTool::Custom, model)
+        }
+    }
+
+    /// Create a scripted agent replaying the given fixtures
+    #[cfg(test)]
+    pub fn fake(fixtures: impl Into<PathBuf>, model: ModelVersion) -> Self {
+        Self {
+            fake: Some(FakeAgentConfig {
+                fixtures: fixtures.into(),
+            }),
+            ..Self::new(AgentTool::Fake, model)
+        }
+    }
+
+    #[cfg(test)]
     pub fn with_iterations(mut self, iterations: u32) -> Self {
-        self.iterations = iterations;
+        self.iterations = Some(iterations);
         self
     }
 
-    /// Get a unique identifier for this agent config
+    #[cfg(test)]
+    pub fn with_ralph_loop(mut self, max_iterations: u32, turns_per_iteration: u32) -> Self {
+        self.ralph_loop = Some(RalphLoopConfig {
+            max_iterations,
+            turns_per_iteration: Some(turns_per_iteration),
+        });
+        self
+    }
+
+    /// Number of times the agent may be invoked for one run
+    pub fn max_invocations(&self) -> u32 {
+        self.ralph_loop
+            .as_ref()
+            .map_or(1, |l| l.max_iterations.max(1))
+    }
+
+    /// Turn budget for a single agent invocation
+    pub fn turns_per_invocation(&self) -> u32 {
+        self.ralph_loop
+            .as_ref()
+            .and_then(|l| l.turns_per_iteration)
+            .or(self.iterations)
+            .unwrap_or_else(default_iterations)
+    }
+
+    /// Total turns the agent may use across all invocations, if its CLI
+    /// enforces a turn limit
+    pub fn total_turn_budget(&self) -> Option<u32> {
+        self.adapter()
+            .is_ok_and(|adapter| adapter.supports_max_turns())
+            .then(|| self.max_invocations() * self.turns_per_invocation())
+    }
+
+    /// Check that the tool is fully defined, and that the model is registered
+    /// and can be driven by the tool
+    pub fn validate(&self) -> Result<()> {
+        let sections = [
+            (AgentTool::Custom, "custom", self.custom.is_some()),
+            (AgentTool::Fake, "fake", self.fake.is_some()),
+        ];
+        for (tool, section, present) in sections {
+            if self.tool == tool && !present {
+                anyhow::bail!(
+                    "Agents with `tool: {}` need a `{}` section",
+                    section,
+                    section
+                );
+            }
+            if self.tool != tool && present {
+                anyhow::bail!(
+                    "A `{}` section requires `tool: {}`, not {}",
+                    section,
+                    section,
+                    self.tool.name()
+                );
+            }
+        }
+        if let Some(custom) = &self.custom {
+            let valid_name = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+            if custom.name.is_empty() || !custom.name.chars().all(valid_name) {
+                anyhow::bail!(
+                    "Custom agent name `{}` may only contain letters, digits, `-` and `_`",
+                    custom.name
+                );
+            }
+            if custom.output_parser.is_some_and(|t| t.adapter().is_none()) {
+                anyhow::bail!("`output_parser` must be a built-in tool");
+            }
+        }
+        let sets_turns = self.iterations.is_some()
+            || self
+                .ralph_loop
+                .as_ref()
+                .is_some_and(|l| l.turns_per_iteration.is_some());
+        if sets_turns && !self.adapter()?.supports_max_turns() {
+            anyhow::bail!(
+                "{} has no turn limit, so `iterations` and `turns_per_iteration` can't be set",
+                self.tool_name()
+            );
+        }
+
+        let Some(info) = self.model.info() else {
+            let known: Vec<&str> = model_registry().ids().collect();
+            anyhow::bail!(
+                "Unknown model '{}' (known models: {}). Add it to a model registry file passed with --models",
+                self.model.id(),
+                known.join(", ")
+            );
+        };
+        // Config-defined tools (custom, fake) can drive any registered model
+        if self.tool.adapter().is_some() && !info.tools.contains(&self.tool) {
+            let tools: Vec<&str> = info.tools.iter().map(AgentTool::name).collect();
+            anyhow::bail!(
+                "Model '{}' cannot be used with {} (supported tools: {})",
+                self.model.id(),
+                self.tool.name(),
+                tools.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    /// Get a unique identifier for this agent config. Ralph-loop settings
+    /// and non-default turn budgets are included, so agents differing only in
+    /// those get distinct ids.
     pub fn id(&self) -> String {
-        format!("{}-{}", self.tool, self.model)
+        let mut id = format!("{}-{}", self.tool_name(), self.model);
+        if let Some(ralph_loop) = &self.ralph_loop {
+            id.push_str(&format!("-ralph{}", ralph_loop.max_iterations));
+        }
+        if self.turns_per_invocation() != default_iterations() {
+            id.push_str(&format!("-{}turns", self.turns_per_invocation()));
+        }
+        // Agents that differ only in their environment are still distinct.
+        // The id names pods, so the values (which may be long, or secret)
+        // are only hashed.
+        if !self.env.is_empty() {
+            id.push_str(&format!("-env{:08x}", env_hash(&self.env) as u32));
+        }
+        id
+    }
+
+    /// Display name of the agent's tool (the CLI name for custom tools)
+    pub fn tool_name(&self) -> String {
+        match &self.custom {
+            Some(custom) => custom.name.clone(),
+            None => self.tool.to_string(),
+        }
+    }
+
+    /// Get the adapter for this agent's tool. Fails for a `custom` or `fake`
+    /// tool without its section.
+    pub fn adapter(&self) -> Result<Arc<dyn AgentAdapter>> {
+        if let Some(custom) = &self.custom {
+            return Ok(Arc::new(CustomAdapter::new(custom.clone())));
+        }
+        if let Some(fake) = &self.fake {
+            return Ok(Arc::new(FakeAdapter::new(&fake.fixtures)));
+        }
+        self.tool.adapter().with_context(|| {
+            format!(
+                "Agents with `tool: {}` need a `{}` section",
+                self.tool.name(),
+                self.tool.name()
+            )
+        })
+    }
+
+    /// Environment variables that must contain credentials for this agent
+    pub fn required_env_vars(&self) -> Result<Vec<String>> {
+        Ok(self.adapter()?.required_env_vars(self.model.provider()))
+    }
+
+    /// Build the invocation parameters for running this agent on a prompt,
+    /// which is also written to `prompt_file`
+    pub fn invocation(&self, prompt: &str, prompt_file: &Path) -> AgentInvocation {
+        AgentInvocation {
+            model: self.model.to_string(),
+            provider: self.model.provider().to_string(),
+            max_turns: self.turns_per_invocation(),
+            prompt: prompt.to_string(),
+            prompt_file: prompt_file.to_path_buf(),
+        }
+    }
+
+    /// Extract turns and token usage from the agent's output, estimating the
+    /// cost from the model's prices when the tool does not report one
+    pub fn parse_output(&self, output: &str) -> AgentOutput {
+        let mut parsed = self
+            .adapter()
+            .map(|adapter| adapter.parse_output(output))
+            .unwrap_or_default();
+        if !parsed.cost_reported {
+            if let (Some(usage), Some(pricing)) =
+                (parsed.token_usage.as_mut(), self.model.pricing())
+            {
+                usage.total_cost_usd = pricing.cost(usage);
+            }
+        }
+        parsed
     }
 }
 
 /// Predefined agent configurations
+#[cfg(test)]
 pub mod presets {
     use super::*;
 
     pub fn claude_opus_45() -> AgentConfig {
-        AgentConfig::new(AgentTool::ClaudeCode, ModelVersion::ClaudeOpus45)
+        AgentConfig::new(AgentTool::ClaudeCode, ModelVersion::new("claude_opus_4_5"))
     }
 
     pub fn claude_sonnet_4() -> AgentConfig {
-        AgentConfig::new(AgentTool::ClaudeCode, ModelVersion::ClaudeSonnet4)
+        AgentConfig::new(AgentTool::ClaudeCode, ModelVersion::new("claude_sonnet_4"))
     }
 
+    #[allow(dead_code)]
     pub fn codex_gpt52_xhigh() -> AgentConfig {
-        AgentConfig::new(AgentTool::Codex, ModelVersion::Gpt52XHigh)
+        AgentConfig::new(AgentTool::Codex, ModelVersion::new("gpt_5_2_xhigh"))
     }
 
+    #[allow(dead_code)]
     pub fn codex_gpt52_high() -> AgentConfig {
-        AgentConfig::new(AgentTool::Codex, ModelVersion::Gpt52High)
+        AgentConfig::new(AgentTool::Codex, ModelVersion::new("gpt_5_2_high"))
     }
 
     pub fn codex_o3() -> AgentConfig {
-        AgentConfig::new(AgentTool::Codex, ModelVersion::O3)
+        AgentConfig::new(AgentTool::Codex, ModelVersion::new("o3"))
     }
 }
 
@@ -169,18 +450,161 @@ mod tests {
         assert_eq!(AgentTool::Codex.to_string(), "codex");
     }
 
+    #[test]
+    fn test_agent_tool_adapter() {
+        for tool in [AgentTool::ClaudeCode, AgentTool::Codex, AgentTool::OpenCode] {
+            assert_eq!(tool.adapter().unwrap().name(), tool.name());
+        }
+        assert!(AgentTool::Custom.adapter().is_none());
+        assert!(AgentTool::Fake.adapter().is_none());
+    }
+
+    #[test]
+    fn test_agent_tool_deserialize() {
+        // Serialized as `open_code`, with the CLI's own name accepted too
+        for name in ["open_code", "opencode"] {
+            let tool: AgentTool = serde_yaml::from_str(name).unwrap();
+            assert_eq!(tool, AgentTool::OpenCode);
+        }
+        assert_eq!(
+            serde_json::to_string(&AgentTool::OpenCode).unwrap(),
+            "\"open_code\""
+        );
+    }
+
     #[test]
     fn test_model_version_display() {
+        let model: ModelVersion = serde_yaml::from_str("claude_opus_4_5").unwrap();
+        assert_eq!(model.id(), "claude_opus_4_5");
+        assert_eq!(model.to_string(), "claude-opus-4-5-20251101");
+        assert_eq!(
+            ModelVersion::new("gpt_5_2_xhigh").to_string(),
+            "gpt-5.2-xhigh"
+        );
         assert_eq!(
-            ModelVersion::ClaudeOpus45.to_string(),
-            "claude-opus-4-5-20251101"
+            ModelVersion::new("unregistered").to_string(),
+            "unregistered"
         );
-        assert_eq!(ModelVersion::Gpt52XHigh.to_string(), "gpt-5.2-xhigh");
+    }
+
+    #[test]
+    fn test_validate_model() {
+        assert!(presets::claude_opus_45().validate().is_ok());
+        assert!(presets::codex_o3().validate().is_ok());
+
+        let wrong_tool = AgentConfig::new(AgentTool::Codex, ModelVersion::new("claude_opus_4_5"));
+        let err = wrong_tool.validate().unwrap_err().to_string();
+        assert!(err.contains("cannot be used with codex"), "{}", err);
+
+        let unknown = AgentConfig::new(AgentTool::ClaudeCode, ModelVersion::new("claude_9"));
+        assert!(unknown.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_output_estimates_cost() {
+        let agent = AgentConfig::new(AgentTool::Codex, ModelVersion::new("gpt_5"));
+        let output = r#"{"type":"turn.completed","usage":{"input_tokens":1000000,"cached_input_tokens":0,"output_tokens":100000}}"#;
+        let usage = agent.parse_output(output).token_usage.unwrap();
+        assert!((usage.total_cost_usd - 2.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ralph_loop_budgets() {
+        let single = presets::claude_opus_45().with_iterations(20);
+        assert_eq!(single.max_invocations(), 1);
+        assert_eq!(single.turns_per_invocation(), 20);
+
+        let looped = single.with_ralph_loop(5, 8);
+        assert_eq!(looped.max_invocations(), 5);
+        assert_eq!(looped.invocation("prompt", Path::new("p.txt")).max_turns, 8);
+        assert_eq!(looped.total_turn_budget(), Some(40));
+    }
+
+    #[test]
+    fn test_turn_budgets_need_a_turn_limit() {
+        let codex = presets::codex_o3();
+        assert_eq!(codex.total_turn_budget(), None);
+        let err = codex
+            .with_iterations(50)
+            .validate()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("codex has no turn limit"), "{}", err);
+
+        // Setting the default explicitly is still rejected
+        let yaml = "{tool: codex, model: o3, iterations: 10}";
+        let agent: AgentConfig = serde_yaml::from_str(yaml).unwrap();
+        assert!(agent.validate().is_err());
+        assert_eq!(presets::codex_o3().turns_per_invocation(), 10);
+
+        let looped = presets::codex_o3().with_ralph_loop(3, 20);
+        assert!(looped.validate().is_err());
+    }
+
+    #[test]
+    fn test_ralph_loop_deserialize() {
+        let yaml = r#"
+tool: claude_code
+model: claude_sonnet_4
+iterations: 15
+ralph_loop:
+  max_iterations: 3
+"#;
+        let agent: AgentConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(agent.max_invocations(), 3);
+        assert_eq!(agent.turns_per_invocation(), 15);
     }
 
     #[test]
     fn test_agent_config_id() {
         let config = presets::claude_opus_45();
         assert_eq!(config.id(), "claude-code-claude-opus-4-5-20251101");
+        assert_eq!(
+            config.clone().with_iterations(50).id(),
+            "claude-code-claude-opus-4-5-20251101-50turns"
+        );
+        assert_eq!(
+            config.clone().with_ralph_loop(5, 10).id(),
+            "claude-code-claude-opus-4-5-20251101-ralph5"
+        );
+
+        let mut verbose = config.clone();
+        verbose.env = BTreeMap::from([("RUST_LOG".to_string(), "debug".to_string())]);
+        assert_eq!(
+            verbose.id(),
+            "claude-code-claude-opus-4-5-20251101-envebd9f6c8"
+        );
+        let mut trace = config;
+        trace.env = BTreeMap::from([("RUST_LOG".to_string(), "trace".to_string())]);
+        assert_ne!(trace.id(), verbose.id());
+    }
+
+    #[test]
+    fn test_custom_agent_deserialize() {
+        let yaml = r#"
+tool: custom
+model: gpt_5
+custom:
+  name: aider
+  install: pip install aider-chat
+  command: aider --model {model} --message-file {prompt_file} --yes
+  env: [OPENAI_API_KEY]
+"#;
+        let agent: AgentConfig = serde_yaml::from_str(yaml).unwrap();
+        agent.validate().unwrap();
+        assert_eq!(agent.id(), "aider-gpt-5");
+        assert_eq!(agent.required_env_vars().unwrap(), vec!["OPENAI_API_KEY"]);
+
+        // The name goes into pod names
+        let mut spaced = agent.clone();
+        spaced.custom.as_mut().unwrap().name = "my aider".to_string();
+        assert!(spaced.validate().is_err());
+
+        // Built without its section, e.g. by a matrix, it fails instead of
+        // panicking
+        let missing = AgentConfig::new(AgentTool::Custom, ModelVersion::new("gpt_5"));
+        assert!(missing.validate().is_err());
+        assert!(missing.adapter().is_err());
+        assert_eq!(missing.total_turn_budget(), None);
     }
 }
@@ -21,6 +21,10 @@ pub struct Args {
     /// Enable verbose logging
     #[arg(short, long, global = true)]
     pub verbose: bool,
+
+    /// Model registry file (YAML) adding to or overriding the built-in models
+    #[arg(long, global = true)]
+    pub models: Option<PathBuf>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -28,6 +32,12 @@ pub enum Command {
     /// Run an evaluation suite
     Run(RunArgs),
 
+    /// Check an evaluation config file and report every problem in it
+    Validate(ValidateArgs),
+
+    /// Print the JSON Schema for evaluation config files
+    Schema(SchemaArgs),
+
     /// Check the status of a running evaluation
     Status(StatusArgs),
 
@@ -45,6 +55,11 @@ pub enum Command {
 
     /// Start the web UI server
     Ui(UiArgs),
+
+    /// Write the pod of one (prompt, agent) combination to a directory, to
+    /// run and debug its scripts without Kubernetes
+    #[command(hide = true)]
+    PodScripts(PodScriptsArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -53,7 +68,7 @@ pub struct RunArgs {
     #[arg(short, long)]
     pub config: PathBuf,
 
-    /// Override the output directory
+    /// Override the output directory (`settings.output_dir`)
     #[arg(short, long)]
     pub output: Option<PathBuf>,
 
@@ -69,9 +84,54 @@ pub struct RunArgs {
     #[arg(long)]
     pub dry_run: bool,
 
-    /// Run tests locally without Kubernetes (for testing the framework)
+    /// Run agents and tests locally without Kubernetes
     #[arg(long)]
     pub local: bool,
+
+    /// With --local, only run the test harness against each eval_path (no agents)
+    #[arg(long, requires = "local")]
+    pub skip_agent: bool,
+
+    /// Only run prompts with this id or matching this glob (repeatable)
+    #[arg(long = "prompt", value_name = "ID|GLOB")]
+    pub prompts: Vec<String>,
+
+    /// Only run agents with this id or model, or matching this glob (repeatable)
+    #[arg(long = "agent", value_name = "ID|GLOB")]
+    pub agents: Vec<String>,
+
+    /// Only run prompts with this tag (repeatable)
+    #[arg(long = "tag", value_name = "TAG")]
+    pub tags: Vec<String>,
+
+    /// Override a setting, e.g. `--set samples=3` (repeatable)
+    #[arg(long = "set", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+    pub overrides: Vec<(String, String)>,
+}
+
+fn parse_key_value(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got `{}`", s)),
+    }
+}
+
+#[derive(Parser, Debug)]
+pub struct ValidateArgs {
+    /// Path to the evaluation config file (YAML)
+    #[arg(short, long)]
+    pub config: PathBuf,
+
+    /// Don't check that the API keys the agents need are set
+    #[arg(long)]
+    pub skip_api_keys: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct SchemaArgs {
+    /// Write the schema to this file instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
 }
 
 #[derive(Parser, Debug)]
@@ -137,7 +197,26 @@ pub struct UiArgs {
     #[arg(short, long, default_value = "8080")]
     pub port: u16,
 
-    /// Directory to scan for evaluation results
-    #[arg(short, long, default_value = ".")]
+    /// Output directory of the evaluations to show (`settings.output_dir`)
+    #[arg(short, long, default_value = "./eval-results")]
     pub results_dir: PathBuf,
 }
+
+#[derive(Parser, Debug)]
+pub struct PodScriptsArgs {
+    /// Path to the evaluation config file (YAML)
+    #[arg(short, long)]
+    pub config: PathBuf,
+
+    /// Directory standing in for the pod's root, where its volumes are created
+    #[arg(long)]
+    pub root: PathBuf,
+
+    /// Only use prompts with this id or matching this glob (repeatable)
+    #[arg(long = "prompt", value_name = "ID|GLOB")]
+    pub prompts: Vec<String>,
+
+    /// Only use agents with this id or model, or matching this glob (repeatable)
+    #[arg(long = "agent", value_name = "ID|GLOB")]
+    pub agents: Vec<String>,
+}
@@ -1,11 +1,14 @@
-use crate::agents::{AgentConfig, AgentTool, ModelVersion};
+use super::{check_config, ConfigErrors};
+use crate::agents::{AgentConfig, AgentTool, ModelVersion, RalphLoopConfig};
 use anyhow::{Context, Result};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
-use std::path::{Path, PathBuf};
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
 
 /// Configuration for an evaluation run
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EvalConfig {
     /// Name of this evaluation
     pub name: String,
@@ -14,28 +17,69 @@ pub struct EvalConfig {
     #[serde(default)]
     pub description: String,
 
+    /// Other config files whose prompts (and, if this file lists no agents,
+    /// agents) are added to this one. Paths are relative to this file and may
+    /// be glob patterns, e.g. `examples/*/eval-config.yaml`.
+    #[serde(default)]
+    pub include: Vec<String>,
+
     /// List of prompts to evaluate
+    #[serde(default)]
     pub prompts: Vec<PromptConfig>,
 
+    /// Shorthand for a config with a single prompt: its text, tested with
+    /// `test_harness` in the config file's directory and identified by `name`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+
+    /// Test harness for the top-level `prompt`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_harness: Option<TestHarness>,
+
     /// List of agents to use
+    #[serde(default)]
     pub agents: Vec<AgentConfig>,
 
+    /// Agents to generate from every combination of the listed values, added
+    /// to `agents`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matrix: Option<AgentMatrix>,
+
     /// Global settings
     #[serde(default)]
     pub settings: EvalSettings,
+
+    /// Selection of prompts and agents to run, from the command line
+    #[serde(skip)]
+    pub filter: RunFilter,
+
+    /// Settings overridden from the command line (`--set`), by key
+    #[serde(skip)]
+    pub overrides: BTreeMap<String, String>,
 }
 
 /// Configuration for a single prompt
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PromptConfig {
     /// Unique identifier for this prompt
     pub id: String,
 
     /// The prompt text
+    #[serde(default)]
     pub prompt: String,
 
+    /// File containing the prompt text, relative to the config file (instead
+    /// of `prompt`)
+    #[serde(default)]
+    pub prompt_file: Option<PathBuf>,
+
     /// Path to the evaluation tests directory
-    pub eval_path: PathBuf,
+    #[serde(default)]
+    pub eval_path: Option<PathBuf>,
+
+    /// Git repository to check out instead of `eval_path`
+    #[serde(default)]
+    pub git_repo: Option<GitRepoConfig>,
 
     /// Test harness to use
     pub test_harness: TestHarness,
@@ -46,10 +90,107 @@ pub struct PromptConfig {
 
     /// Optional timeout override in hours
     pub timeout_hours: Option<u32>,
+
+    /// Environment variables for the setup commands, agent and tests
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+
+    /// Tags for selecting prompts with `run --tag`
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Number of runs per agent for this prompt, overriding
+    /// `settings.samples`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = 1))]
+    pub samples: Option<u32>,
+}
+
+/// A git repository providing a prompt's starter code and tests
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitRepoConfig {
+    /// Repository URL (anything `git clone` accepts, including `file://` URLs
+    /// and local paths)
+    pub url: String,
+
+    /// Commit, branch or tag to check out (defaults to the default branch)
+    #[serde(default, rename = "ref")]
+    pub git_ref: Option<String>,
+
+    /// Subdirectory of the repository to use as the workspace
+    #[serde(default)]
+    pub subdir: Option<PathBuf>,
+}
+
+impl fmt::Display for GitRepoConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.url)?;
+        if let Some(git_ref) = &self.git_ref {
+            write!(f, "@{}", git_ref)?;
+        }
+        if let Some(subdir) = &self.subdir {
+            write!(f, ":{}", subdir.display())?;
+        }
+        Ok(())
+    }
+}
+
+impl PromptConfig {
+    /// Environment for a run of this prompt with `agent`: the prompt's `env`,
+    /// with the agent's `env` taking precedence
+    pub fn run_env(&self, agent: &AgentConfig) -> BTreeMap<String, String> {
+        let mut env = self.env.clone();
+        env.extend(agent.env.clone());
+        env
+    }
+
+    /// Check that the prompt has exactly one valid source for its workspace
+    pub fn validate(&self) -> Result<()> {
+        let repo = match (&self.eval_path, &self.git_repo) {
+            (Some(_), Some(_)) => {
+                anyhow::bail!("`eval_path` and `git_repo` are mutually exclusive")
+            }
+            (None, None) => anyhow::bail!("One of `eval_path` or `git_repo` is required"),
+            (Some(_), None) => return Ok(()),
+            (None, Some(repo)) => repo,
+        };
+
+        if repo.url.trim().is_empty() {
+            anyhow::bail!("`git_repo.url` must not be empty");
+        }
+        if repo
+            .git_ref
+            .as_deref()
+            .is_some_and(|r| r.is_empty() || r.starts_with('-'))
+        {
+            anyhow::bail!("`git_repo.ref` must be a commit, branch or tag name");
+        }
+        if let Some(subdir) = &repo.subdir {
+            let escapes = subdir
+                .components()
+                .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+            if escapes {
+                anyhow::bail!(
+                    "`git_repo.subdir` must be a relative path inside the repository, got {:?}",
+                    subdir
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Human-readable description of where the workspace comes from
+    pub fn source(&self) -> String {
+        match (&self.git_repo, &self.eval_path) {
+            (Some(repo), _) => repo.to_string(),
+            (None, Some(path)) => path.display().to_string(),
+            (None, None) => String::new(),
+        }
+    }
 }
 
 /// Supported test harnesses
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum TestHarness {
     /// Rust cargo test
@@ -57,27 +198,82 @@ pub enum TestHarness {
         /// Cargo features to enable
         #[serde(default)]
         features: Vec<String>,
+        /// Disable the default features (`--no-default-features`)
+        #[serde(default)]
+        no_default_features: bool,
         /// Run with release mode
         #[serde(default)]
         release: bool,
+        /// Test every package in the workspace (`--workspace`)
+        #[serde(default)]
+        workspace: bool,
+        /// Package to test (`-p`)
+        #[serde(default)]
+        package: Option<String>,
+        /// Integration test target to run (`--test`), e.g. `api_conformance`
+        #[serde(default)]
+        test_name: Option<String>,
+        /// Only run tests whose names contain this string
+        #[serde(default)]
+        filter: Option<String>,
+        /// Number of threads running tests (`--test-threads`); 1 runs them
+        /// one at a time
+        #[serde(default)]
+        test_threads: Option<u32>,
+        /// Show the tests' output (`--nocapture`)
+        #[serde(default)]
+        nocapture: bool,
+        /// Extra arguments passed to the test binaries after `--`
+        #[serde(default)]
+        test_args: Vec<String>,
+        /// Time limit for the tests, in seconds
+        #[serde(default)]
+        timeout_seconds: Option<u64>,
+        /// Not supported: `cargo test` writes no report file
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        report: Option<TestReport>,
     },
     /// Node.js npm test
     Npm {
         /// Test script name (default: "test")
         #[serde(default = "default_npm_script")]
         script: String,
+        /// Test runner the script runs, whose structured results are read
+        /// instead of scraping the console output
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reporter: Option<NpmReporter>,
+        /// Time limit for the tests, in seconds
+        #[serde(default)]
+        timeout_seconds: Option<u64>,
+        /// Report file the tests write
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        report: Option<TestReport>,
     },
-    /// Python pytest
+    /// Python pytest. Results are read from a JUnit report (or the
+    /// configured `report`), which the harness passes pytest the flags to
+    /// write.
     Pytest {
         /// Extra pytest arguments
         #[serde(default)]
         args: Vec<String>,
+        /// Time limit for the tests, in seconds
+        #[serde(default)]
+        timeout_seconds: Option<u64>,
+        /// Report file the tests write
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        report: Option<TestReport>,
     },
-    /// Go test
+    /// Go test, run with `-json`
     Go {
         /// Package path
         #[serde(default = "default_go_package")]
         package: String,
+        /// Time limit for the tests, in seconds
+        #[serde(default)]
+        timeout_seconds: Option<u64>,
+        /// Not supported: `go test` writes no report file
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        report: Option<TestReport>,
     },
     /// Custom command
     Custom {
@@ -86,9 +282,125 @@ pub enum TestHarness {
         /// Arguments
         #[serde(default)]
         args: Vec<String>,
+        /// Time limit for the tests, in seconds
+        #[serde(default)]
+        timeout_seconds: Option<u64>,
+        /// Report file the tests write
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        report: Option<TestReport>,
     },
+    /// Shell commands: an optional build step, then the tests. A failing build
+    /// is recorded as `build_failed` rather than as failing tests.
+    Shell {
+        /// Shell command that builds the project before the tests run
+        #[serde(default)]
+        build_command: Option<String>,
+        /// Shell command that runs the tests
+        test_command: String,
+        /// Time limit for each of the build and test commands, in seconds
+        #[serde(default)]
+        timeout_seconds: Option<u64>,
+        /// Report file the tests write
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        report: Option<TestReport>,
+    },
+}
+
+/// A machine-readable report written by the tests. When it exists after the
+/// run it is parsed instead of the console output, which is kept as the raw
+/// output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TestReport {
+    /// Format of the report
+    pub format: ReportFormat,
+    /// Path of the report file, relative to the workspace
+    pub path: PathBuf,
+}
+
+/// Formats of test report files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    /// JUnit XML, as written by cargo-nextest, pytest `--junitxml`, jest-junit
+    /// and gotestsum
+    Junit,
+    /// JSON from the pytest-json-report plugin (`--json-report`)
+    PytestJson,
+    /// JSON from `jest --json` or vitest's `json` reporter
+    JestJson,
+    /// JSON from mocha's `json` reporter
+    MochaJson,
+    /// TAP (Test Anything Protocol)
+    Tap,
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ReportFormat::Junit => "junit",
+            ReportFormat::PytestJson => "pytest_json",
+            ReportFormat::JestJson => "jest_json",
+            ReportFormat::MochaJson => "mocha_json",
+            ReportFormat::Tap => "tap",
+        };
+        f.write_str(name)
+    }
+}
+
+/// JavaScript test runners the npm harness can read structured results from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NpmReporter {
+    /// Jest, with `--json --outputFile`
+    Jest,
+    /// Vitest, with `--reporter=json --outputFile`
+    Vitest,
+    /// Mocha, with its `json` reporter
+    Mocha,
+    /// Any runner printing TAP, such as `node --test`; read from the console
+    Tap,
+}
+
+impl NpmReporter {
+    /// Format of the report file the runner writes, if it writes one
+    fn report_format(self) -> Option<ReportFormat> {
+        match self {
+            NpmReporter::Jest | NpmReporter::Vitest => Some(ReportFormat::JestJson),
+            NpmReporter::Mocha => Some(ReportFormat::MochaJson),
+            NpmReporter::Tap => None,
+        }
+    }
+
+    /// Arguments that make the runner write its report to `path`
+    fn args(self, path: &Path) -> Vec<String> {
+        let path = path.to_string_lossy();
+        match self {
+            NpmReporter::Jest => vec!["--json".to_string(), format!("--outputFile={}", path)],
+            NpmReporter::Vitest => vec![
+                "--reporter=json".to_string(),
+                format!("--outputFile={}", path),
+            ],
+            NpmReporter::Mocha => vec![
+                "--reporter".to_string(),
+                "json".to_string(),
+                "--reporter-option".to_string(),
+                format!("output={}", path),
+            ],
+            NpmReporter::Tap => vec![],
+        }
+    }
 }
 
+/// Where the pytest harness writes its report in pods unless one is
+/// configured (local runs use a file per run, see
+/// [`TestHarness::with_default_report_path`]). Outside the workspace, so an
+/// eval_path tested in place isn't written to
+const PYTEST_REPORT_FILE: &str = "/tmp/anode-pytest-report.xml";
+
+/// Where the npm harness has the reporter write its results unless a report
+/// is configured. Outside the workspace, like the pytest report
+const NPM_REPORT_FILE: &str = "/tmp/anode-npm-report.json";
+
 fn default_npm_script() -> String {
     "test".to_string()
 }
@@ -100,38 +412,328 @@ fn default_go_package() -> String {
 impl TestHarness {
     /// Get the command to run tests
     pub fn test_command(&self) -> (String, Vec<String>) {
-        match self {
-            TestHarness::Cargo { features, release } => {
+        let command = match self {
+            TestHarness::Cargo {
+                features,
+                no_default_features,
+                release,
+                workspace,
+                package,
+                test_name,
+                filter,
+                test_threads,
+                nocapture,
+                test_args,
+                ..
+            } => {
                 let mut args = vec!["test".to_string()];
+                if *workspace {
+                    args.push("--workspace".to_string());
+                }
+                if let Some(package) = package {
+                    args.push("-p".to_string());
+                    args.push(package.clone());
+                }
+                if let Some(test_name) = test_name {
+                    args.push("--test".to_string());
+                    args.push(test_name.clone());
+                }
                 if !features.is_empty() {
                     args.push("--features".to_string());
                     args.push(features.join(","));
                 }
+                if *no_default_features {
+                    args.push("--no-default-features".to_string());
+                }
                 if *release {
                     args.push("--release".to_string());
                 }
+                if let Some(filter) = filter {
+                    args.push(filter.clone());
+                }
+
+                // Arguments for the libtest harness in each test binary
+                let mut libtest_args = Vec::new();
+                if let Some(threads) = test_threads {
+                    libtest_args.push(format!("--test-threads={}", threads));
+                }
+                if *nocapture {
+                    libtest_args.push("--nocapture".to_string());
+                }
+                libtest_args.extend(test_args.iter().cloned());
+                if !libtest_args.is_empty() {
+                    args.push("--".to_string());
+                    args.extend(libtest_args);
+                }
+
                 // Don't use --format=json as it requires nightly
                 ("cargo".to_string(), args)
             }
-            TestHarness::Npm { script } => {
-                ("npm".to_string(), vec!["run".to_string(), script.clone()])
+            TestHarness::Npm {
+                script, reporter, ..
+            } => {
+                let mut args = vec!["run".to_string(), script.clone()];
+                if let (Some(reporter), Some(report)) = (reporter, self.report()) {
+                    let reporter_args = reporter.args(&report.path);
+                    if !reporter_args.is_empty() {
+                        // Passed on to the script's command
+                        args.push("--".to_string());
+                        args.extend(reporter_args);
+                    }
+                }
+                ("npm".to_string(), args)
             }
-            TestHarness::Pytest { args } => {
+            TestHarness::Pytest { args, .. } => {
                 let mut full_args = vec!["-v".to_string(), "--tb=short".to_string()];
+                if let Some(report) = self.report() {
+                    let path = report.path.to_string_lossy();
+                    match report.format {
+                        ReportFormat::Junit => full_args.push(format!("--junitxml={}", path)),
+                        ReportFormat::PytestJson => {
+                            full_args.push("--json-report".to_string());
+                            full_args.push(format!("--json-report-file={}", path));
+                        }
+                        // Rejected by `validate`
+                        ReportFormat::JestJson | ReportFormat::MochaJson | ReportFormat::Tap => {}
+                    }
+                }
                 full_args.extend(args.clone());
                 ("pytest".to_string(), full_args)
             }
-            TestHarness::Go { package } => (
+            TestHarness::Go { package, .. } => (
                 "go".to_string(),
-                vec!["test".to_string(), "-v".to_string(), package.clone()],
+                vec!["test".to_string(), "-json".to_string(), package.clone()],
+            ),
+            TestHarness::Custom { command, args, .. } => (command.clone(), args.clone()),
+            TestHarness::Shell { test_command, .. } => shell_command(test_command),
+        };
+        with_timeout(command, self.timeout_seconds())
+    }
+
+    /// Get the command that builds the project before the tests, for harnesses
+    /// with a separate build step
+    pub fn build_command(&self) -> Option<(String, Vec<String>)> {
+        match self {
+            TestHarness::Shell {
+                build_command: Some(build_command),
+                ..
+            } => Some(with_timeout(
+                shell_command(build_command),
+                self.timeout_seconds(),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Report file the tests write: the configured one, or by default a JUnit
+    /// report for pytest and the report of an npm reporter
+    pub fn report(&self) -> Option<TestReport> {
+        match self {
+            TestHarness::Npm {
+                reporter: Some(reporter),
+                report: None,
+                ..
+            } => reporter.report_format().map(|format| TestReport {
+                format,
+                path: PathBuf::from(NPM_REPORT_FILE),
+            }),
+            TestHarness::Pytest { report: None, .. } => Some(TestReport {
+                format: ReportFormat::Junit,
+                path: PathBuf::from(PYTEST_REPORT_FILE),
+            }),
+            TestHarness::Cargo { report, .. }
+            | TestHarness::Npm { report, .. }
+            | TestHarness::Pytest { report, .. }
+            | TestHarness::Go { report, .. }
+            | TestHarness::Custom { report, .. }
+            | TestHarness::Shell { report, .. } => report.clone(),
+        }
+    }
+
+    /// This harness with its default report (see [`Self::report`]) written to
+    /// `path` instead, so concurrent local runs don't share one file
+    pub fn with_default_report_path(&self, path: &Path) -> TestHarness {
+        let mut harness = self.clone();
+        let default = match &mut harness {
+            TestHarness::Npm { report, .. } | TestHarness::Pytest { report, .. }
+                if report.is_none() =>
+            {
+                report
+            }
+            _ => return harness,
+        };
+        *default = self.report().map(|report| TestReport {
+            format: report.format,
+            path: path.to_path_buf(),
+        });
+        harness
+    }
+
+    /// Check that a configured report is one the harness can have written
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            TestHarness::Npm {
+                reporter: Some(reporter),
+                report: Some(report),
+                ..
+            } => match reporter.report_format() {
+                Some(format) if format != report.format => anyhow::bail!(
+                    "the reporter writes `{}` reports, not `{}`",
+                    format,
+                    report.format
+                ),
+                _ => Ok(()),
+            },
+            TestHarness::Pytest {
+                report: Some(report),
+                ..
+            } if !matches!(
+                report.format,
+                ReportFormat::Junit | ReportFormat::PytestJson
+            ) =>
+            {
+                anyhow::bail!(
+                    "pytest writes `junit` or `pytest_json` reports, not `{}`",
+                    report.format
+                )
+            }
+            TestHarness::Cargo {
+                report: Some(_), ..
+            } => anyhow::bail!(
+                "`cargo test` writes no report; run cargo-nextest with `!custom` to read its JUnit report"
+            ),
+            TestHarness::Go {
+                report: Some(_), ..
+            } => anyhow::bail!(
+                "`go test` writes no report; its `-json` output already gives per-test results"
             ),
-            TestHarness::Custom { command, args } => (command.clone(), args.clone()),
+            _ => Ok(()),
         }
     }
+
+    /// Time limit for each harness command, in seconds
+    pub fn timeout_seconds(&self) -> Option<u64> {
+        match self {
+            TestHarness::Cargo {
+                timeout_seconds, ..
+            }
+            | TestHarness::Npm {
+                timeout_seconds, ..
+            }
+            | TestHarness::Pytest {
+                timeout_seconds, ..
+            }
+            | TestHarness::Go {
+                timeout_seconds, ..
+            }
+            | TestHarness::Custom {
+                timeout_seconds, ..
+            }
+            | TestHarness::Shell {
+                timeout_seconds, ..
+            } => *timeout_seconds,
+        }
+    }
+}
+
+/// Run a shell string with bash
+fn shell_command(script: &str) -> (String, Vec<String>) {
+    (
+        "bash".to_string(),
+        vec!["-c".to_string(), script.to_string()],
+    )
+}
+
+/// Run a command under coreutils `timeout` if there is a limit, which exits
+/// with 124 when the limit is hit
+fn with_timeout(
+    (command, args): (String, Vec<String>),
+    timeout_seconds: Option<u64>,
+) -> (String, Vec<String>) {
+    match timeout_seconds {
+        Some(seconds) => {
+            let mut timeout_args = vec![seconds.to_string(), command];
+            timeout_args.extend(args);
+            ("timeout".to_string(), timeout_args)
+        }
+        None => (command, args),
+    }
+}
+
+/// A sweep over agent parameters, expanded into one agent per combination
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AgentMatrix {
+    /// Agent CLIs to run
+    pub tool: Vec<AgentTool>,
+
+    /// Models to run with each tool. Pairs the model registry says the tool
+    /// can't drive are skipped.
+    pub model: Vec<ModelVersion>,
+
+    /// Turn budgets to try (defaults to the agent default). Tools whose CLI
+    /// has no turn limit only run with the default.
+    #[serde(default)]
+    pub iterations: Vec<u32>,
+
+    /// Ralph-loop settings to try; `null` runs the agent once
+    #[serde(default)]
+    pub ralph_loop: Vec<Option<RalphLoopConfig>>,
+
+    /// Number of runs per prompt for the generated agents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = 1))]
+    pub samples: Option<u32>,
+}
+
+impl AgentMatrix {
+    /// Generate the agent for every combination of the listed values
+    pub fn agents(&self) -> Vec<AgentConfig> {
+        let iterations = match self.iterations.as_slice() {
+            [] => vec![None],
+            values => values.iter().copied().map(Some).collect(),
+        };
+        let ralph_loops = match self.ralph_loop.as_slice() {
+            [] => vec![None],
+            values => values.to_vec(),
+        };
+
+        let mut agents = Vec::new();
+        for tool in &self.tool {
+            for model in &self.model {
+                let unsupported = tool.adapter().is_some()
+                    && model.info().is_some_and(|info| !info.tools.contains(tool));
+                if unsupported {
+                    continue;
+                }
+                let limits_turns = tool.adapter().is_none_or(|a| a.supports_max_turns());
+                let turn_budgets = if limits_turns {
+                    &iterations[..]
+                } else {
+                    &[None]
+                };
+                for turns in turn_budgets {
+                    for ralph_loop in &ralph_loops {
+                        let sets_turns = ralph_loop
+                            .as_ref()
+                            .is_some_and(|l| l.turns_per_iteration.is_some());
+                        if sets_turns && !limits_turns {
+                            continue;
+                        }
+                        let mut agent = AgentConfig::new(*tool, model.clone());
+                        agent.iterations = *turns;
+                        agent.ralph_loop = ralph_loop.clone();
+                        agent.samples = self.samples;
+                        agents.push(agent);
+                    }
+                }
+            }
+        }
+        agents
+    }
 }
 
 /// Global evaluation settings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EvalSettings {
     /// Default timeout in hours
     #[serde(default = "default_timeout")]
@@ -149,6 +751,11 @@ pub struct EvalSettings {
     #[serde(default = "default_cleanup")]
     pub cleanup_on_complete: bool,
 
+    /// Number of independent runs for each (prompt, agent) combination
+    #[serde(default = "default_samples")]
+    #[schemars(range(min = 1))]
+    pub samples: u32,
+
     /// API keys configuration
     #[serde(default)]
     pub api_keys: ApiKeysConfig,
@@ -161,6 +768,7 @@ impl Default for EvalSettings {
             output_dir: default_output_dir(),
             default_iterations: default_iterations(),
             cleanup_on_complete: default_cleanup(),
+            samples: default_samples(),
             api_keys: ApiKeysConfig::default(),
         }
     }
@@ -182,8 +790,113 @@ fn default_cleanup() -> bool {
     true
 }
 
+fn default_samples() -> u32 {
+    1
+}
+
+impl EvalSettings {
+    /// Override a setting from a `key=value` string, where `key` is a dotted
+    /// path like `api_keys.env_vars` and `value` is parsed as YAML
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let mut settings = serde_yaml::to_value(&*self)?;
+        let mut field = &mut settings;
+        for segment in key.split('.') {
+            field = field
+                .as_mapping_mut()
+                .and_then(|mapping| mapping.get_mut(segment))
+                .with_context(|| format!("Unknown setting `{}`", key))?;
+        }
+        *field = serde_yaml::from_str(value)
+            .with_context(|| format!("Invalid value for `{}`: {}", key, value))?;
+
+        let settings: EvalSettings = serde_yaml::from_value(settings)
+            .with_context(|| format!("Invalid value for `{}`: {}", key, value))?;
+        if settings.samples == 0 {
+            anyhow::bail!("`samples` must be at least 1");
+        }
+        *self = settings;
+        Ok(())
+    }
+}
+
+/// Which prompts and agents of a config to run. Each list matches if any of
+/// its entries does (all prompts or agents if empty); prompt ids, agent ids
+/// and agent model ids may be glob patterns.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunFilter {
+    /// Prompt ids or patterns
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prompts: Vec<String>,
+    /// Agent ids, model ids or patterns
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agents: Vec<String>,
+    /// Prompt tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl RunFilter {
+    pub fn new(prompts: Vec<String>, agents: Vec<String>, tags: Vec<String>) -> Result<Self> {
+        for pattern in prompts.iter().chain(&agents) {
+            glob::Pattern::new(pattern)
+                .with_context(|| format!("Invalid pattern `{}`", pattern))?;
+        }
+        Ok(Self {
+            prompts,
+            agents,
+            tags,
+        })
+    }
+
+    /// Whether the filter selects everything
+    pub fn is_empty(&self) -> bool {
+        self.prompts.is_empty() && self.agents.is_empty() && self.tags.is_empty()
+    }
+
+    pub fn matches_prompt(&self, prompt: &PromptConfig) -> bool {
+        let id =
+            self.prompts.is_empty() || self.prompts.iter().any(|p| glob_matches(p, &prompt.id));
+        let tag = self.tags.is_empty() || self.tags.iter().any(|t| prompt.tags.contains(t));
+        id && tag
+    }
+
+    pub fn matches_agent(&self, agent: &AgentConfig) -> bool {
+        let id = agent.id();
+        self.agents.is_empty()
+            || self
+                .agents
+                .iter()
+                .any(|p| glob_matches(p, &id) || glob_matches(p, agent.model.id()))
+    }
+}
+
+impl fmt::Display for RunFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts = [
+            ("prompt", &self.prompts),
+            ("agent", &self.agents),
+            ("tag", &self.tags),
+        ];
+        let mut first = true;
+        for (flag, values) in parts {
+            for value in values {
+                if !first {
+                    write!(f, " ")?;
+                }
+                write!(f, "--{} {}", flag, value)?;
+                first = false;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn glob_matches(pattern: &str, value: &str) -> bool {
+    glob::Pattern::new(pattern).is_ok_and(|p| p.matches(value))
+}
+
 /// API keys configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 pub struct ApiKeysConfig {
     /// Environment variable names for API keys
     /// The values will be read from the environment
@@ -210,18 +923,40 @@ impl ApiKeysConfig {
 
         Ok(keys)
     }
-}
 
-impl EvalConfig {
-    /// Load configuration from a YAML file
-    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let content = std::fs::read_to_string(path.as_ref())
-            .context(format!("Failed to read config file: {:?}", path.as_ref()))?;
+    /// Whether `var` is one of the configured keys
+    pub fn contains(&self, var: &str) -> bool {
+        self.direct.contains_key(var) || self.env_vars.iter().any(|v| v == var)
+    }
 
-        let config: EvalConfig =
-            serde_yaml::from_str(&content).context("Failed to parse config file")?;
+    /// Why the key `var` won't be available to agents, if it won't be
+    pub fn missing(&self, var: &str) -> Option<&'static str> {
+        if self.direct.contains_key(var) {
+            None
+        } else if !self.contains(var) {
+            Some("it is not listed in settings.api_keys")
+        } else if std::env::var_os(var).is_none() {
+            Some("it is not set in the environment")
+        } else {
+            None
+        }
+    }
+}
 
-        Ok(config)
+impl EvalConfig {
+    /// Load configuration from a YAML file, reporting every problem found by
+    /// [`check_config`] at once. Included files are merged in, prompt files
+    /// are read, and relative paths are resolved against the directory of the
+    /// file they appear in.
+    pub fn load<P: AsRef<Path>>(path: P, check_api_keys: bool) -> Result<Self> {
+        let path = path.as_ref();
+        check_config(path, check_api_keys).map_err(|issues| {
+            ConfigErrors {
+                file: path.to_path_buf(),
+                issues,
+            }
+            .into()
+        })
     }
 
     /// Save configuration to a YAML file
@@ -232,44 +967,147 @@ impl EvalConfig {
         Ok(())
     }
 
-    /// Generate all (prompt, agent) combinations for this evaluation
+    /// Restrict the evaluation to the prompts and agents `filter` selects
+    pub fn with_filter(mut self, filter: RunFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Override settings from `key=value` pairs (see [`EvalSettings::set`])
+    pub fn with_overrides(mut self, overrides: &[(String, String)]) -> Result<Self> {
+        for (key, value) in overrides {
+            self.settings.set(key, value)?;
+            self.overrides.insert(key.clone(), value.clone());
+        }
+        Ok(self)
+    }
+
+    /// Check that the API keys the selected agents require are available
+    pub fn check_api_keys(&self) -> Result<()> {
+        let mut problems = Vec::new();
+        for agent in self.selected_agents() {
+            // A missing custom/fake section is reported when loading
+            let Ok(required) = agent.required_env_vars() else {
+                continue;
+            };
+            for var in required {
+                if let Some(problem) = self.settings.api_keys.missing(&var) {
+                    problems.push(format!(
+                        "agent `{}` requires {} but {}",
+                        agent.id(),
+                        var,
+                        problem
+                    ));
+                }
+            }
+        }
+        if !problems.is_empty() {
+            anyhow::bail!("Missing API keys:\n  - {}", problems.join("\n  - "));
+        }
+        Ok(())
+    }
+
+    /// Prompts selected by the filter
+    pub fn selected_prompts(&self) -> impl Iterator<Item = &PromptConfig> {
+        self.prompts
+            .iter()
+            .filter(|p| self.filter.matches_prompt(p))
+    }
+
+    /// Agents selected by the filter
+    pub fn selected_agents(&self) -> impl Iterator<Item = &AgentConfig> {
+        self.agents.iter().filter(|a| self.filter.matches_agent(a))
+    }
+
+    /// Generate all selected (prompt, agent) combinations for this evaluation
     pub fn combinations(&self) -> Vec<(PromptConfig, AgentConfig)> {
         let mut result = Vec::new();
-        for prompt in &self.prompts {
-            for agent in &self.agents {
+        for prompt in self.selected_prompts() {
+            for agent in self.selected_agents() {
                 result.push((prompt.clone(), agent.clone()));
             }
         }
         result
     }
 
+    /// Number of runs for a combination. Prompt and agent overrides take
+    /// precedence over `settings.samples`; if both are set the larger wins.
+    pub fn samples_for(&self, prompt: &PromptConfig, agent: &AgentConfig) -> u32 {
+        match (prompt.samples, agent.samples) {
+            (Some(p), Some(a)) => p.max(a),
+            (Some(n), None) | (None, Some(n)) => n,
+            (None, None) => self.settings.samples,
+        }
+    }
+
+    /// Generate every run of this evaluation: each combination repeated for
+    /// its number of samples, with the 1-based sample index
+    pub fn scheduled_runs(&self) -> Vec<(PromptConfig, AgentConfig, u32)> {
+        let mut result = Vec::new();
+        for (prompt, agent) in self.combinations() {
+            for sample in 1..=self.samples_for(&prompt, &agent) {
+                result.push((prompt.clone(), agent.clone(), sample));
+            }
+        }
+        result
+    }
+
     /// Generate a sample configuration
     pub fn sample() -> Self {
         Self {
             name: "Sample Evaluation".to_string(),
             description: "A sample evaluation configuration".to_string(),
+            include: vec![],
+            prompt: None,
+            test_harness: None,
             prompts: vec![PromptConfig {
                 id: "hello-world".to_string(),
                 prompt: "Create a function that returns 'Hello, World!' and write tests for it."
                     .to_string(),
-                eval_path: PathBuf::from("./evals/hello-world"),
+                prompt_file: None,
+                eval_path: Some(PathBuf::from("./evals/hello-world")),
+                git_repo: None,
                 test_harness: TestHarness::Cargo {
                     features: vec![],
+                    no_default_features: false,
                     release: false,
+                    workspace: false,
+                    package: None,
+                    test_name: None,
+                    filter: None,
+                    test_threads: None,
+                    nocapture: false,
+                    test_args: vec![],
+                    timeout_seconds: None,
+                    report: None,
                 },
                 setup_commands: vec![],
                 timeout_hours: None,
+                env: BTreeMap::new(),
+                tags: vec![],
+                samples: None,
             }],
+            matrix: None,
             agents: vec![
                 AgentConfig {
                     tool: AgentTool::ClaudeCode,
-                    model: ModelVersion::ClaudeOpus45,
-                    iterations: 10,
+                    model: ModelVersion::new("claude_opus_4_5"),
+                    iterations: Some(10),
+                    ralph_loop: None,
+                    custom: None,
+                    fake: None,
+                    env: BTreeMap::new(),
+                    samples: None,
                 },
                 AgentConfig {
                     tool: AgentTool::Codex,
-                    model: ModelVersion::Gpt52XHigh,
-                    iterations: 10,
+                    model: ModelVersion::new("gpt_5_2_xhigh"),
+                    iterations: None,
+                    ralph_loop: None,
+                    custom: None,
+                    fake: None,
+                    env: BTreeMap::new(),
+                    samples: None,
                 },
             ],
             settings: EvalSettings {
@@ -282,6 +1120,8 @@ impl EvalConfig {
                 },
                 ..Default::default()
             },
+            filter: RunFilter::default(),
+            overrides: BTreeMap::new(),
         }
     }
 }
@@ -289,12 +1129,16 @@ impl EvalConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::BTreeSet;
 
     #[test]
     fn test_sample_config() {
         let config = EvalConfig::sample();
         assert_eq!(config.prompts.len(), 1);
         assert_eq!(config.agents.len(), 2);
+        for agent in &config.agents {
+            agent.validate().unwrap();
+        }
     }
 
     #[test]
@@ -305,15 +1149,354 @@ mod tests {
     }
 
     #[test]
-    fn test_cargo_test_command() {
-        let harness = TestHarness::Cargo {
-            features: vec!["feature1".to_string()],
-            release: true,
+    fn test_filtered_combinations() {
+        let mut config = EvalConfig::sample();
+        let mut tagged = config.prompts[0].clone();
+        tagged.id = "crm-api".to_string();
+        tagged.tags = vec!["slow".to_string()];
+        config.prompts.push(tagged);
+        assert_eq!(config.combinations().len(), 4);
+
+        let filter = |prompts: &[&str], agents: &[&str], tags: &[&str]| {
+            let strings = |v: &[&str]| v.iter().map(|s| s.to_string()).collect();
+            RunFilter::new(strings(prompts), strings(agents), strings(tags)).unwrap()
         };
+        let only = |config: &EvalConfig, filter| config.clone().with_filter(filter).combinations();
+
+        let combos = only(&config, filter(&["hello-*"], &[], &[]));
+        assert!(combos.iter().all(|(p, _)| p.id == "hello-world"));
+        assert_eq!(combos.len(), 2);
+        assert_eq!(
+            only(&config, filter(&[], &["claude_opus_4_5"], &[])).len(),
+            2
+        );
+        assert_eq!(only(&config, filter(&[], &["codex-*"], &["slow"])).len(), 1);
+        assert!(only(&config, filter(&["missing"], &[], &[])).is_empty());
+        assert!(RunFilter::new(vec!["[".to_string()], vec![], vec![]).is_err());
+    }
+
+    #[test]
+    fn test_settings_overrides() {
+        let mut settings = EvalSettings::default();
+        settings.set("samples", "3").unwrap();
+        settings.set("api_keys.env_vars", "[A, B]").unwrap();
+        assert_eq!(settings.samples, 3);
+        assert_eq!(settings.api_keys.env_vars, vec!["A", "B"]);
+
+        assert!(settings.set("sample", "3").is_err());
+        assert!(settings.set("samples", "many").is_err());
+        assert!(settings.set("samples", "0").is_err());
+        assert_eq!(settings.samples, 3);
+    }
+
+    #[test]
+    fn test_api_keys_are_checked_for_selected_agents() {
+        let overrides = [(
+            "api_keys".to_string(),
+            "{direct: {ANTHROPIC_API_KEY: sk-test}}".to_string(),
+        )];
+        let config = EvalConfig::sample().with_overrides(&overrides).unwrap();
+        assert_eq!(config.overrides["api_keys"], overrides[0].1);
+        let error = config.check_api_keys().unwrap_err().to_string();
+        assert!(error.contains("requires OPENAI_API_KEY but it is not listed in settings.api_keys"));
+
+        let claude = RunFilter::new(vec![], vec!["claude-code-*".to_string()], vec![]).unwrap();
+        assert!(config.with_filter(claude).check_api_keys().is_ok());
+    }
+
+    #[test]
+    fn test_scheduled_runs_repeat_samples() {
+        let mut config = EvalConfig::sample();
+        config.settings.samples = 3;
+        assert_eq!(config.scheduled_runs().len(), 6);
+
+        // Overrides apply per combination, and the larger one wins
+        config.agents[1].samples = Some(2);
+        let runs = config.scheduled_runs();
+        assert_eq!(runs.len(), 5);
+        let codex: Vec<u32> = runs
+            .iter()
+            .filter(|(_, agent, _)| agent.tool == AgentTool::Codex)
+            .map(|(_, _, sample)| *sample)
+            .collect();
+        assert_eq!(codex, vec![1, 2]);
+
+        config.prompts[0].samples = Some(4);
+        assert_eq!(config.scheduled_runs().len(), 8);
+    }
+
+    #[test]
+    fn test_matrix_expansion() {
+        let matrix: AgentMatrix = serde_yaml::from_str(
+            r#"
+tool: [claude_code, opencode, codex]
+model: [claude_opus_4_5]
+iterations: [10, 50]
+ralph_loop: [null, {max_iterations: 5}]
+"#,
+        )
+        .unwrap();
+        let agents = matrix.agents();
+
+        // Codex can't drive Opus, so that pair is skipped, and OpenCode has
+        // no turn limit, so it only runs with the default
+        assert_eq!(agents.len(), 6);
+        assert!(agents.iter().all(|a| a.tool != AgentTool::Codex));
+        let ids: BTreeSet<String> = agents.iter().map(AgentConfig::id).collect();
+        assert_eq!(ids.len(), agents.len());
+        assert!(ids.contains("claude-code-claude-opus-4-5-20251101-ralph5-50turns"));
+        assert!(ids.contains("opencode-claude-opus-4-5-20251101-ralph5"));
+        assert!(agents.iter().all(|a| a.validate().is_ok()));
+    }
+
+    #[test]
+    fn test_cargo_test_command() {
+        let harness: TestHarness =
+            serde_yaml::from_str("!cargo\nfeatures: [feature1]\nrelease: true\n").unwrap();
         let (cmd, args) = harness.test_command();
         assert_eq!(cmd, "cargo");
-        assert!(args.contains(&"test".to_string()));
-        assert!(args.contains(&"--release".to_string()));
+        assert_eq!(args, ["test", "--features", "feature1", "--release"]);
+    }
+
+    #[test]
+    fn test_cargo_test_selection_and_libtest_args() {
+        let yaml = r#"
+!cargo
+package: crm_api
+test_name: api_conformance
+no_default_features: true
+filter: create_person
+test_threads: 1
+nocapture: true
+test_args: [--include-ignored]
+"#;
+        let harness: TestHarness = serde_yaml::from_str(yaml).unwrap();
+        let (_, args) = harness.test_command();
+        assert_eq!(
+            args,
+            [
+                "test",
+                "-p",
+                "crm_api",
+                "--test",
+                "api_conformance",
+                "--no-default-features",
+                "create_person",
+                "--",
+                "--test-threads=1",
+                "--nocapture",
+                "--include-ignored",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pytest_and_go_reports() {
+        let harness: TestHarness = serde_yaml::from_str("!pytest\nargs: [-x]").unwrap();
+        assert_eq!(
+            harness.test_command().1,
+            [
+                "-v",
+                "--tb=short",
+                "--junitxml=/tmp/anode-pytest-report.xml",
+                "-x"
+            ]
+        );
+        assert_eq!(harness.report().unwrap().format, ReportFormat::Junit);
+
+        let yaml = "!pytest\nreport:\n  format: pytest_json\n  path: report.json";
+        let harness: TestHarness = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            harness.test_command().1,
+            [
+                "-v",
+                "--tb=short",
+                "--json-report",
+                "--json-report-file=report.json"
+            ]
+        );
+        assert!(harness.validate().is_ok());
+
+        // pytest can't write the JavaScript runners' reports
+        let yaml = "!pytest\nreport:\n  format: jest_json\n  path: report.json";
+        let harness: TestHarness = serde_yaml::from_str(yaml).unwrap();
+        assert!(harness.validate().is_err());
+
+        let harness: TestHarness = serde_yaml::from_str("!go {}").unwrap();
+        assert_eq!(harness.test_command().1, ["test", "-json", "./..."]);
+        assert!(harness.report().is_none());
+
+        // Neither `go test` nor `cargo test` writes a report file
+        for tag in ["!go", "!cargo"] {
+            let yaml = format!("{}\nreport:\n  format: junit\n  path: junit.xml", tag);
+            let harness: TestHarness = serde_yaml::from_str(&yaml).unwrap();
+            assert!(harness.validate().is_err(), "{}", tag);
+        }
+
+        // Every harness can be given a time limit
+        let harness: TestHarness = serde_yaml::from_str("!go\ntimeout_seconds: 90").unwrap();
+        let (cmd, args) = harness.test_command();
+        assert_eq!(cmd, "timeout");
+        assert_eq!(args, ["90", "go", "test", "-json", "./..."]);
+    }
+
+    #[test]
+    fn test_npm_reporters() {
+        let harness: TestHarness = serde_yaml::from_str("!npm\nreporter: jest").unwrap();
+        assert_eq!(
+            harness.test_command().1,
+            [
+                "run",
+                "test",
+                "--",
+                "--json",
+                "--outputFile=/tmp/anode-npm-report.json"
+            ]
+        );
+        assert_eq!(harness.report().unwrap().format, ReportFormat::JestJson);
+
+        let yaml = "!npm\nscript: test:unit\nreporter: mocha\nreport:\n  format: mocha_json\n  path: out/mocha.json";
+        let harness: TestHarness = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            harness.test_command().1,
+            [
+                "run",
+                "test:unit",
+                "--",
+                "--reporter",
+                "json",
+                "--reporter-option",
+                "output=out/mocha.json"
+            ]
+        );
+        assert!(harness.validate().is_ok());
+
+        // A report has to be in the format the reporter writes
+        let yaml = "!npm\nreporter: jest\nreport:\n  format: junit\n  path: junit.xml";
+        let harness: TestHarness = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            harness.validate().unwrap_err().to_string(),
+            "the reporter writes `jest_json` reports, not `junit`"
+        );
+
+        // TAP is read from the console
+        let harness: TestHarness = serde_yaml::from_str("!npm\nreporter: tap").unwrap();
+        assert_eq!(harness.test_command().1, ["run", "test"]);
+        assert!(harness.report().is_none());
+    }
+
+    #[test]
+    fn test_shell_harness() {
+        let yaml = r#"
+!shell
+build_command: cargo build --release
+test_command: cargo test -- --test-threads=1
+timeout_seconds: 600
+"#;
+        let harness: TestHarness = serde_yaml::from_str(yaml).unwrap();
+        let (cmd, args) = harness.test_command();
+        assert_eq!(cmd, "timeout");
+        assert_eq!(
+            args,
+            ["600", "bash", "-c", "cargo test -- --test-threads=1"]
+        );
+        let (cmd, args) = harness.build_command().unwrap();
+        assert_eq!(cmd, "timeout");
+        assert_eq!(args, ["600", "bash", "-c", "cargo build --release"]);
+
+        let harness = TestHarness::Shell {
+            build_command: None,
+            test_command: "make test".to_string(),
+            timeout_seconds: None,
+            report: None,
+        };
+        assert_eq!(
+            harness.test_command(),
+            (
+                "bash".to_string(),
+                vec!["-c".to_string(), "make test".to_string()]
+            )
+        );
+        assert!(harness.build_command().is_none());
+    }
+
+    #[test]
+    fn test_harness_report() {
+        let yaml = r#"
+!shell
+test_command: cargo nextest run --profile ci
+report:
+  format: junit
+  path: target/nextest/ci/junit.xml
+"#;
+        let harness: TestHarness = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            harness.report(),
+            Some(TestReport {
+                format: ReportFormat::Junit,
+                path: PathBuf::from("target/nextest/ci/junit.xml"),
+            })
+        );
+        assert!(TestHarness::Shell {
+            build_command: None,
+            test_command: "make test".to_string(),
+            timeout_seconds: None,
+            report: None,
+        }
+        .report()
+        .is_none());
+
+        // Default reports can be moved to a per-run path, configured ones
+        // stay where the tests write them
+        let path = Path::new("/tmp/anode-report-1234");
+        let pytest: TestHarness = serde_yaml::from_str("!pytest {}").unwrap();
+        let moved = pytest.with_default_report_path(path);
+        assert_eq!(moved.report().unwrap().path, path);
+        assert!(moved
+            .test_command()
+            .1
+            .contains(&"--junitxml=/tmp/anode-report-1234".to_string()));
+        let npm: TestHarness = serde_yaml::from_str("!npm\nreporter: jest").unwrap();
+        let moved = npm.with_default_report_path(path);
+        assert_eq!(moved.report().unwrap().format, ReportFormat::JestJson);
+        assert_eq!(moved.report().unwrap().path, path);
+        let npm: TestHarness = serde_yaml::from_str("!npm\nreporter: tap").unwrap();
+        assert!(npm.with_default_report_path(path).report().is_none());
+        assert_eq!(
+            harness.with_default_report_path(path).report().unwrap().path,
+            Path::new("target/nextest/ci/junit.xml")
+        );
+    }
+
+    #[test]
+    fn test_git_repo_prompt() {
+        let yaml = r#"
+id: from-git
+prompt: Fix the bug
+git_repo:
+  url: file:///srv/repos/evals.git
+  ref: v1.2
+  subdir: rust/hello
+test_harness: !cargo {}
+"#;
+        let prompt: PromptConfig = serde_yaml::from_str(yaml).unwrap();
+        prompt.validate().unwrap();
+        assert_eq!(
+            prompt.source(),
+            "file:///srv/repos/evals.git@v1.2:rust/hello"
+        );
+
+        let mut both = prompt.clone();
+        both.eval_path = Some(PathBuf::from("./evals"));
+        assert!(both.validate().is_err());
+
+        let mut neither = prompt.clone();
+        neither.git_repo = None;
+        assert!(neither.validate().is_err());
+
+        let mut escaping = prompt;
+        escaping.git_repo.as_mut().unwrap().subdir = Some(PathBuf::from("../outside"));
+        assert!(escaping.validate().is_err());
     }
 
     #[test]
@@ -0,0 +1,188 @@
+//! `${VAR}` interpolation of config files
+//!
+//! References are replaced in the string values of the parsed YAML, so
+//! comments are left alone and a substituted value can't change the
+//! document's structure. A plain (unquoted) value that becomes a number or
+//! boolean is read as one, so `samples: ${SAMPLES:-3}` works.
+
+use serde_yaml::Value;
+use std::collections::BTreeSet;
+
+/// A `${...}` reference that could not be replaced
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnresolvedVar {
+    /// Dotted path of the value holding the reference
+    pub path: String,
+    pub message: String,
+}
+
+/// Replace references in every string value of `value`. `plain` holds the
+/// dotted paths of the values written as plain scalars. Every reference that
+/// can't be resolved is reported.
+pub fn interpolate_values(
+    value: &mut Value,
+    plain: &BTreeSet<String>,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Vec<UnresolvedVar> {
+    let mut unresolved = Vec::new();
+    interpolate_value(value, "", plain, lookup, &mut unresolved);
+    unresolved
+}
+
+fn interpolate_value(
+    value: &mut Value,
+    path: &str,
+    plain: &BTreeSet<String>,
+    lookup: &impl Fn(&str) -> Option<String>,
+    unresolved: &mut Vec<UnresolvedVar>,
+) {
+    let child = |segment: &str| match path {
+        "" => segment.to_string(),
+        _ => format!("{}.{}", path, segment),
+    };
+    match value {
+        Value::String(text) => {
+            let (output, messages) = interpolate(text, lookup);
+            unresolved.extend(messages.into_iter().map(|message| UnresolvedVar {
+                path: path.to_string(),
+                message,
+            }));
+            if output == *text {
+                return;
+            }
+            *value = match serde_yaml::from_str(&output) {
+                Ok(typed @ (Value::Number(_) | Value::Bool(_))) if plain.contains(path) => typed,
+                _ => Value::String(output),
+            };
+        }
+        Value::Sequence(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                interpolate_value(item, &child(&i.to_string()), plain, lookup, unresolved);
+            }
+        }
+        Value::Mapping(mapping) => {
+            for (key, item) in mapping.iter_mut() {
+                let key = key.as_str().unwrap_or_default();
+                interpolate_value(item, &child(key), plain, lookup, unresolved);
+            }
+        }
+        // Tags (`!cargo`) are not part of the path
+        Value::Tagged(tagged) => {
+            interpolate_value(&mut tagged.value, path, plain, lookup, unresolved)
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+}
+
+/// Replace `${VAR}` and `${VAR:-default}` in `text` with values from `lookup`.
+/// The default applies when the variable is unset or empty, and `$${` is left
+/// in place as a literal `${`. Returns the messages of the references that
+/// can't be resolved.
+fn interpolate(text: &str, lookup: &impl Fn(&str) -> Option<String>) -> (String, Vec<String>) {
+    let mut output = String::with_capacity(text.len());
+    let mut unresolved = Vec::new();
+
+    let mut rest = text;
+    while let Some(start) = rest.find('$') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(escaped) = rest.strip_prefix("$${") {
+            output.push_str("${");
+            rest = escaped;
+            continue;
+        }
+        let Some(reference) = rest.strip_prefix("${") else {
+            output.push('$');
+            rest = &rest[1..];
+            continue;
+        };
+        let Some(end) = reference.find('}') else {
+            unresolved.push("unterminated `${` (write `$${` for a literal `${`)".to_string());
+            output.push_str(rest);
+            rest = "";
+            break;
+        };
+
+        let (name, default) = match reference[..end].split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (&reference[..end], None),
+        };
+        let value = lookup(name).filter(|v| !v.is_empty() || default.is_none());
+        match (value, default) {
+            _ if !is_var_name(name) => {
+                unresolved.push(format!("invalid environment variable name `{}`", name))
+            }
+            (Some(value), _) => output.push_str(&value),
+            (None, Some(default)) => output.push_str(default),
+            (None, None) => unresolved.push(format!(
+                "environment variable `{}` is not set (use `${{{}:-default}}` for a default)",
+                name, name
+            )),
+        }
+        rest = &reference[end + 1..];
+    }
+    output.push_str(rest);
+
+    (output, unresolved)
+}
+
+fn is_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "HOME" => Some("/home/eval".to_string()),
+            "EMPTY" => Some(String::new()),
+            "QUOTED" => Some("it's: \"here\"\n# not a comment".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let (output, unresolved) = interpolate(
+            "${HOME}/evals ${PORT:-8080} '${EMPTY}${EMPTY:-x}' $HOME $$PATH $${PATH}",
+            &lookup,
+        );
+        assert!(unresolved.is_empty(), "{:?}", unresolved);
+        assert_eq!(output, "/home/eval/evals 8080 'x' $HOME $$PATH ${PATH}");
+    }
+
+    #[test]
+    fn test_only_string_values_are_interpolated() {
+        let yaml = "# uses ${MISSING}\n\
+                    eval_path: ${HOME}/evals\n\
+                    samples: ${SAMPLES:-3}\n\
+                    port: '${PORT:-8080}'\n\
+                    test_harness: !shell\n  test_command: echo ${QUOTED}\n";
+        let mut value: Value = serde_yaml::from_str(yaml).unwrap();
+        let plain = BTreeSet::from(["samples".to_string()]);
+        assert!(interpolate_values(&mut value, &plain, &lookup).is_empty());
+
+        let expected = "eval_path: /home/eval/evals\n\
+                        samples: 3\n\
+                        port: '8080'\n\
+                        test_harness: !shell\n  test_command: |-\n    echo it's: \"here\"\n    # not a comment\n";
+        assert_eq!(serde_yaml::to_string(&value).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_unresolved_references() {
+        let yaml = "a: 1\nb: ${MISSING}\nc: [x, '${1X} ${OPEN']\n";
+        let mut value: Value = serde_yaml::from_str(yaml).unwrap();
+        let unresolved = interpolate_values(&mut value, &BTreeSet::new(), &lookup);
+        let paths: Vec<&str> = unresolved.iter().map(|u| u.path.as_str()).collect();
+        assert_eq!(paths, vec!["b", "c.1", "c.1"]);
+        assert!(unresolved[0].message.contains("`MISSING` is not set"));
+    }
+}
@@ -1,5 +1,11 @@
 mod args;
 mod config;
+mod interpolate;
+mod schema;
+mod validate;
 
 pub use args::*;
 pub use config::*;
+pub use interpolate::*;
+pub use schema::*;
+pub use validate::*;
@@ -0,0 +1,120 @@
+//! JSON Schema for eval config files
+//!
+//! The schema is derived from the config types, so editors using it (e.g. via
+//! the YAML language server) always see the fields `EvalConfig::load` accepts.
+
+use super::EvalConfig;
+use schemars::gen::SchemaSettings;
+use schemars::schema::{RootSchema, Schema, SchemaObject};
+use schemars::visit::{visit_schema_object, Visitor};
+
+/// Generate the JSON Schema for `eval-config.yaml` files
+pub fn config_schema() -> RootSchema {
+    SchemaSettings::draft07()
+        .with_visitor(YamlConfigConventions)
+        .into_generator()
+        .into_root_schema_for::<EvalConfig>()
+}
+
+/// Adjusts the derived schema to how configs are written in YAML.
+///
+/// Enums with data (like `TestHarness`) are selected with a YAML tag such as
+/// `!cargo`, which JSON Schema can't see: a validator only sees the tagged
+/// mapping. So instead of a one-property object per variant, each variant is
+/// described by its fields alone. Unknown fields are also disallowed, since
+/// loading rejects them.
+#[derive(Debug, Clone)]
+struct YamlConfigConventions;
+
+impl Visitor for YamlConfigConventions {
+    fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+        if let Some(subschemas) = schema.subschemas.as_mut() {
+            let tagged = subschemas
+                .one_of
+                .as_ref()
+                .is_some_and(|variants| variants.iter().all(|v| tagged_variant(v).is_some()));
+            if tagged {
+                let variants = subschemas.one_of.take().unwrap_or_default();
+                // Variants whose fields all have defaults match the same
+                // mappings, so `oneOf` would reject them
+                subschemas.any_of = Some(variants.into_iter().map(untag_variant).collect());
+            }
+        }
+
+        if let Some(object) = schema.object.as_mut() {
+            if !object.properties.is_empty() && object.additional_properties.is_none() {
+                object.additional_properties = Some(Box::new(Schema::Bool(false)));
+            }
+        }
+
+        visit_schema_object(self, schema)
+    }
+}
+
+/// The tag and content schema of an externally tagged enum variant
+fn tagged_variant(schema: &Schema) -> Option<(&String, &Schema)> {
+    let Schema::Object(schema) = schema else {
+        return None;
+    };
+    let object = schema.object.as_ref()?;
+    match object.properties.iter().next() {
+        Some((tag, content)) if object.properties.len() == 1 && object.required.contains(tag) => {
+            Some((tag, content))
+        }
+        _ => None,
+    }
+}
+
+/// Replace a variant's `{tag: content}` schema with its content, titled with
+/// the YAML tag
+fn untag_variant(variant: Schema) -> Schema {
+    let Some((tag, content)) = tagged_variant(&variant) else {
+        return variant;
+    };
+    let mut content = content.clone().into_object();
+    let metadata = content.metadata();
+    if metadata.title.is_none() {
+        metadata.title = Some(format!("!{}", tag));
+    }
+    if metadata.description.is_none() {
+        if let Schema::Object(variant) = &variant {
+            metadata.description = variant
+                .metadata
+                .as_ref()
+                .and_then(|m| m.description.clone());
+        }
+    }
+    Schema::Object(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_test_harness_variants_are_untagged() {
+        let schema = serde_json::to_value(config_schema()).unwrap();
+        let harness = &schema["definitions"]["TestHarness"];
+        assert!(harness["oneOf"].is_null());
+
+        let variants = harness["anyOf"].as_array().unwrap();
+        let cargo = variants
+            .iter()
+            .find(|v| v["title"] == "!cargo")
+            .expect("cargo variant");
+        assert!(cargo["properties"]["features"].is_object());
+        assert_eq!(cargo["additionalProperties"], false);
+    }
+
+    #[test]
+    fn test_checked_in_schema_is_current() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/eval-config.schema.json");
+        let checked_in = std::fs::read_to_string(path).unwrap_or_default();
+        let generated = serde_json::to_string_pretty(&config_schema()).unwrap();
+        assert!(
+            checked_in.trim_end() == generated,
+            "eval-config.schema.json is out of date; regenerate it with \
+             `anode-eval schema --output eval-config.schema.json`"
+        );
+    }
+}
@@ -0,0 +1,899 @@
+//! Strict config validation
+//!
+//! Serde silently ignores unknown fields and only reports the first error it
+//! hits, so configs are checked in a second pass that collects every problem
+//! (unknown fields, missing directories, duplicate ids, missing credentials)
+//! and points each one at its line in the YAML file. Included config files and
+//! prompt files are resolved in the same pass.
+
+use super::{interpolate_values, EvalConfig, PromptConfig};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::{Marker, TScalarStyle};
+
+/// A single problem found in a config file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    /// Config file the problem is in (the top-level file or an include)
+    pub file: PathBuf,
+    /// Line in the YAML file (1-based), when it can be located
+    pub line: Option<usize>,
+    /// Dotted path of the offending value (e.g. `prompts.0.eval_path`)
+    pub path: String,
+    /// What is wrong
+    pub message: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.file.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+        }
+        if !self.path.is_empty() {
+            write!(f, ": {}", self.path)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Every problem found in a config file
+#[derive(Debug, thiserror::Error)]
+pub struct ConfigErrors {
+    pub file: PathBuf,
+    pub issues: Vec<ConfigIssue>,
+}
+
+impl fmt::Display for ConfigErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} problem(s) in config file {:?}:",
+            self.issues.len(),
+            self.file
+        )?;
+        for issue in &self.issues {
+            write!(f, "\n  - {}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+/// Load and check a config file and everything it includes.
+///
+/// Relative paths (`eval_path`, `prompt_file`, `include` patterns and fake
+/// agent fixtures) are resolved against the directory of the file they appear
+/// in, and prompt files are read into `prompt`. Required API keys are only
+/// checked when `check_api_keys` is set, since dry runs and `--skip-agent`
+/// runs don't need them.
+pub fn check_config(path: &Path, check_api_keys: bool) -> Result<EvalConfig, Vec<ConfigIssue>> {
+    let mut checker = Checker::default();
+    let loaded = checker.load(path);
+    if let Some(loaded) = &loaded {
+        checker.check_samples(path, "settings", Some(loaded.config.settings.samples));
+        checker.check_prompts(loaded);
+        checker.check_agents(loaded, check_api_keys);
+    }
+
+    match loaded {
+        Some(loaded) if checker.issues.is_empty() => Ok(loaded.config),
+        _ => Err(checker.issues),
+    }
+}
+
+/// Make relative paths in the config relative to the config file's directory
+fn resolve_paths(config: &mut EvalConfig, base_dir: &Path) {
+    let resolve = |path: &mut PathBuf| {
+        if path.is_relative() {
+            *path = base_dir.join(&*path).components().collect();
+        }
+    };
+
+    for prompt in &mut config.prompts {
+        if let Some(eval_path) = &mut prompt.eval_path {
+            resolve(eval_path);
+        }
+        if let Some(prompt_file) = &mut prompt.prompt_file {
+            resolve(prompt_file);
+        }
+    }
+    for agent in &mut config.agents {
+        if let Some(fake) = &mut agent.fake {
+            resolve(&mut fake.fixtures);
+        }
+    }
+}
+
+/// serde_ignored marks newtype and enum variant layers with `?`; the YAML
+/// document has no such layer
+fn normalize_path(path: &str) -> String {
+    path.split('.')
+        .filter(|segment| *segment != "?")
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Dotted value path of a deserialization error
+fn error_path(path: &serde_path_to_error::Path) -> String {
+    path.iter()
+        .filter_map(|segment| match segment {
+            serde_path_to_error::Segment::Seq { index } => Some(index.to_string()),
+            serde_path_to_error::Segment::Map { key } => Some(key.clone()),
+            // Enum variants are YAML tags, which aren't part of the path
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// A serde_yaml error without its position, which is in the document written
+/// back out after interpolation rather than in the config file
+fn without_position(e: &serde_yaml::Error) -> String {
+    let message = e.to_string();
+    let position = e
+        .location()
+        .map(|l| format!(" at line {} column {}", l.line(), l.column()));
+    match position.and_then(|p| message.strip_suffix(&p).map(str::to_string)) {
+        Some(message) => message,
+        None => message,
+    }
+}
+
+/// A config with its includes merged in, remembering where each prompt and
+/// agent was defined so problems can be reported against the right file
+struct Loaded {
+    config: EvalConfig,
+    prompts: Vec<Origin>,
+    agents: Vec<Origin>,
+}
+
+/// Where a prompt or agent is defined: its file and value path there
+#[derive(Clone)]
+struct Origin {
+    file: PathBuf,
+    path: String,
+}
+
+impl Origin {
+    /// Origins of the first `count` items of the list at `list`
+    fn all(file: &Path, list: &str, count: usize) -> Vec<Self> {
+        (0..count)
+            .map(|index| Origin {
+                file: file.to_path_buf(),
+                path: format!("{}.{}", list, index),
+            })
+            .collect()
+    }
+}
+
+#[derive(Default)]
+struct Checker {
+    /// Value paths mapped to lines, per config file
+    lines: BTreeMap<PathBuf, BTreeMap<String, usize>>,
+    /// Files being loaded, outermost first, to detect include cycles
+    loading: Vec<PathBuf>,
+    issues: Vec<ConfigIssue>,
+}
+
+impl Checker {
+    /// Parse a config file after interpolating `${VAR}`s, resolve its paths
+    /// and prompt files, expand its agent matrix and merge in its includes.
+    /// Returns `None` if the file can't be parsed at all.
+    fn load(&mut self, file: &Path) -> Option<Loaded> {
+        let source = match std::fs::read_to_string(file) {
+            Ok(source) => source,
+            Err(e) => {
+                self.report(file, "", format!("failed to read config file: {}", e));
+                return None;
+            }
+        };
+
+        let mut index = LineIndex::default();
+        // Syntax errors are reported by serde_yaml; whatever was indexed
+        // before the error is still useful
+        let _ = Parser::new_from_str(&source).load(&mut index, false);
+        self.lines.insert(file.to_path_buf(), index.lines);
+
+        let mut value: serde_yaml::Value = match serde_yaml::from_str(&source) {
+            Ok(value) => value,
+            Err(e) => {
+                self.issues.push(ConfigIssue {
+                    file: file.to_path_buf(),
+                    line: e.location().map(|l| l.line()),
+                    path: String::new(),
+                    message: e.to_string(),
+                });
+                return None;
+            }
+        };
+        let lookup = |name: &str| std::env::var(name).ok();
+        for var in interpolate_values(&mut value, &index.plain, &lookup) {
+            self.report(file, &var.path, var.message);
+        }
+
+        // Deserialized from the interpolated document written back out, so
+        // plain values like `PORT: 3000` still read as strings where one is
+        // expected. Problems are located by their path in the original file.
+        let source = serde_yaml::to_string(&value).unwrap_or_default();
+        let mut unknown = Vec::new();
+        let mut ignored = |path: serde_ignored::Path| unknown.push(path.to_string());
+        let deserializer = serde_yaml::Deserializer::from_str(&source);
+        let parsed: Result<EvalConfig, _> = serde_path_to_error::deserialize(
+            serde_ignored::Deserializer::new(deserializer, &mut ignored),
+        );
+        // Unknown keys seen before a deserialize error are still reported
+        for path in unknown {
+            let path = normalize_path(&path);
+            let field = path.rsplit('.').next().unwrap_or_default().to_string();
+            self.report(file, &path, format!("unknown field `{}`", field));
+        }
+        let mut config = match parsed {
+            Ok(config) => config,
+            Err(e) => {
+                let path = error_path(e.path());
+                self.report(file, &path, without_position(e.inner()));
+                return None;
+            }
+        };
+
+        let base_dir = file.parent().unwrap_or(Path::new(""));
+        resolve_paths(&mut config, base_dir);
+        self.read_prompt_files(file, &mut config);
+
+        let include = std::mem::take(&mut config.include);
+        let mut loaded = Loaded {
+            prompts: Origin::all(file, "prompts", config.prompts.len()),
+            agents: Origin::all(file, "agents", config.agents.len()),
+            config,
+        };
+        if let Some(prompt) = self.single_prompt(file, base_dir, &mut loaded.config) {
+            loaded.config.prompts.push(prompt);
+            loaded.prompts.push(Origin {
+                file: file.to_path_buf(),
+                path: "prompt".to_string(),
+            });
+        }
+
+        if let Some(matrix) = loaded.config.matrix.take() {
+            let agents = matrix.agents();
+            if agents.is_empty() {
+                self.report(
+                    file,
+                    "matrix",
+                    "expands to no agents: no listed model can be used with the listed tools",
+                );
+            }
+            loaded.agents.extend(agents.iter().map(|_| Origin {
+                file: file.to_path_buf(),
+                path: "matrix".to_string(),
+            }));
+            loaded.config.agents.extend(agents);
+        }
+
+        self.loading.push(canonical(file));
+        for (i, pattern) in include.iter().enumerate() {
+            self.include(file, i, &base_dir.join(pattern), &mut loaded);
+        }
+        self.loading.pop();
+
+        Some(loaded)
+    }
+
+    /// Merge the config files matching `pattern` into `loaded`. Their prompts
+    /// are added; their agents only when the including file defines none.
+    fn include(&mut self, file: &Path, i: usize, pattern: &Path, loaded: &mut Loaded) {
+        let path = format!("include.{}", i);
+        let matches: Vec<PathBuf> = match glob::glob(&pattern.to_string_lossy()) {
+            Ok(paths) => paths.filter_map(|p| p.ok()).collect(),
+            Err(e) => {
+                self.report(file, &path, format!("invalid include pattern: {}", e));
+                return;
+            }
+        };
+        if matches.is_empty() {
+            self.report(file, &path, format!("{:?} matches no files", pattern));
+            return;
+        }
+
+        let inherit_agents = loaded.config.agents.is_empty();
+        for included in matches {
+            if self.loading.contains(&canonical(&included)) {
+                self.report(file, &path, format!("{:?} includes itself", included));
+                continue;
+            }
+            let Some(other) = self.load(&included) else {
+                continue;
+            };
+
+            loaded.config.prompts.extend(other.config.prompts);
+            loaded.prompts.extend(other.prompts);
+            if inherit_agents {
+                for (agent, origin) in other.config.agents.into_iter().zip(other.agents) {
+                    if !loaded.config.agents.contains(&agent) {
+                        loaded.config.agents.push(agent);
+                        loaded.agents.push(origin);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Turn the top-level `prompt` and `test_harness` shorthand into a prompt
+    /// tested in the config file's directory
+    fn single_prompt(
+        &mut self,
+        file: &Path,
+        base_dir: &Path,
+        config: &mut EvalConfig,
+    ) -> Option<PromptConfig> {
+        match (config.prompt.take(), config.test_harness.take()) {
+            (Some(prompt), Some(test_harness)) => Some(PromptConfig {
+                id: config.name.clone(),
+                prompt,
+                prompt_file: None,
+                eval_path: Some(base_dir.join(".").components().collect()),
+                git_repo: None,
+                test_harness,
+                setup_commands: vec![],
+                timeout_hours: None,
+                env: BTreeMap::new(),
+                tags: vec![],
+                samples: None,
+            }),
+            (Some(_), None) => {
+                self.report(
+                    file,
+                    "prompt",
+                    "a top-level `prompt` needs a `test_harness`",
+                );
+                None
+            }
+            (None, Some(_)) => {
+                self.report(
+                    file,
+                    "test_harness",
+                    "a top-level `test_harness` needs a `prompt`",
+                );
+                None
+            }
+            (None, None) => None,
+        }
+    }
+
+    /// Replace each `prompt_file` with the prompt text it contains
+    fn read_prompt_files(&mut self, file: &Path, config: &mut EvalConfig) {
+        for (i, prompt) in config.prompts.iter_mut().enumerate() {
+            let path = format!("prompts.{}", i);
+            match (&prompt.prompt_file, prompt.prompt.is_empty()) {
+                (None, false) => {}
+                (None, true) => {
+                    self.report(file, &path, "one of `prompt` or `prompt_file` is required")
+                }
+                (Some(_), false) => self.report(
+                    file,
+                    &path,
+                    "`prompt` and `prompt_file` are mutually exclusive",
+                ),
+                (Some(prompt_file), true) => match std::fs::read_to_string(prompt_file) {
+                    Ok(text) => prompt.prompt = text,
+                    Err(e) => self.report(
+                        file,
+                        &format!("{}.prompt_file", path),
+                        format!("failed to read {:?}: {}", prompt_file, e),
+                    ),
+                },
+            }
+        }
+    }
+
+    /// Record an issue, locating it at `path` or its nearest located parent
+    fn report(&mut self, file: &Path, path: &str, message: impl Into<String>) {
+        let lines = self.lines.get(file);
+        let mut lookup = path;
+        let line = loop {
+            if let Some(line) = lines.and_then(|lines| lines.get(lookup)) {
+                break Some(*line);
+            }
+            match lookup.rfind('.') {
+                Some(i) => lookup = &lookup[..i],
+                None => break None,
+            }
+        };
+        self.issues.push(ConfigIssue {
+            file: file.to_path_buf(),
+            line,
+            path: path.to_string(),
+            message: message.into(),
+        });
+    }
+
+    fn check_samples(&mut self, file: &Path, path: &str, samples: Option<u32>) {
+        if samples == Some(0) {
+            self.report(file, &format!("{}.samples", path), "must be at least 1");
+        }
+    }
+
+    /// API keys are passed to runs separately, so `env` can't also set them
+    fn check_env(
+        &mut self,
+        file: &Path,
+        path: &str,
+        env: &BTreeMap<String, String>,
+        loaded: &Loaded,
+    ) {
+        for key in env.keys() {
+            if loaded.config.settings.api_keys.contains(key) {
+                self.report(
+                    file,
+                    &format!("{}.env.{}", path, key),
+                    format!("`{}` is an API key in settings.api_keys", key),
+                );
+            }
+        }
+    }
+
+    fn check_prompts(&mut self, loaded: &Loaded) {
+        let mut seen = BTreeSet::new();
+        for (prompt, origin) in loaded.config.prompts.iter().zip(&loaded.prompts) {
+            let path = &origin.path;
+            if !seen.insert(prompt.id.as_str()) {
+                self.report(
+                    &origin.file,
+                    &format!("{}.id", path),
+                    format!("duplicate prompt id `{}`", prompt.id),
+                );
+            }
+            if let Err(e) = prompt.validate() {
+                self.report(&origin.file, path, e.to_string());
+            }
+            if let Err(e) = prompt.test_harness.validate() {
+                let path = format!("{}.test_harness.report.format", path);
+                self.report(&origin.file, &path, e.to_string());
+            }
+            self.check_samples(&origin.file, path, prompt.samples);
+            self.check_env(&origin.file, path, &prompt.env, loaded);
+            if let Some(eval_path) = &prompt.eval_path {
+                if !eval_path.is_dir() {
+                    self.report(
+                        &origin.file,
+                        &format!("{}.eval_path", path),
+                        format!("directory {:?} does not exist", eval_path),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_agents(&mut self, loaded: &Loaded, check_api_keys: bool) {
+        let api_keys = &loaded.config.settings.api_keys;
+        let mut seen = BTreeSet::new();
+        for (agent, origin) in loaded.config.agents.iter().zip(&loaded.agents) {
+            let path = &origin.path;
+            self.check_samples(&origin.file, path, agent.samples);
+            self.check_env(&origin.file, path, &agent.env, loaded);
+            if let Err(e) = agent.validate() {
+                self.report(&origin.file, path, e.to_string());
+                continue;
+            }
+            if !seen.insert(agent.id()) {
+                self.report(
+                    &origin.file,
+                    path,
+                    format!("duplicate agent `{}`", agent.id()),
+                );
+            }
+            if !check_api_keys {
+                continue;
+            }
+            // A missing custom/fake section is reported by `validate`
+            let Ok(required) = agent.required_env_vars() else {
+                continue;
+            };
+            for var in required {
+                if let Some(problem) = api_keys.missing(&var) {
+                    let message =
+                        format!("agent `{}` requires {} but {}", agent.id(), var, problem);
+                    self.report(&origin.file, path, message);
+                }
+            }
+        }
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Maps dotted value paths to the line they start on
+#[derive(Default)]
+struct LineIndex {
+    lines: BTreeMap<String, usize>,
+    /// Paths of the values written as plain (unquoted) scalars
+    plain: BTreeSet<String>,
+    path: Vec<String>,
+    nodes: Vec<Node>,
+}
+
+enum Node {
+    /// A mapping, holding the key whose value comes next
+    Mapping(Option<String>),
+    /// A sequence, holding the index of the next item
+    Sequence(usize),
+}
+
+impl LineIndex {
+    fn begin_value(&mut self, line: usize) {
+        let segment = match self.nodes.last_mut() {
+            Some(Node::Mapping(key)) => key.take().unwrap_or_default(),
+            Some(Node::Sequence(index)) => {
+                *index += 1;
+                (*index - 1).to_string()
+            }
+            None => String::new(),
+        };
+        self.path.push(segment);
+        self.lines.entry(self.path_string()).or_insert(line);
+    }
+
+    fn end_value(&mut self) {
+        self.path.pop();
+    }
+
+    fn path_string(&self) -> String {
+        // The first segment belongs to the document root
+        self.path
+            .iter()
+            .skip(1)
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+}
+
+impl MarkedEventReceiver for LineIndex {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        let line = mark.line();
+        match event {
+            Event::Scalar(value, ..) if matches!(self.nodes.last(), Some(Node::Mapping(None))) => {
+                // A mapping key: point at the key rather than its value,
+                // which may start lines later
+                self.path.push(value.clone());
+                self.lines.entry(self.path_string()).or_insert(line);
+                self.path.pop();
+                self.nodes.pop();
+                self.nodes.push(Node::Mapping(Some(value)));
+            }
+            Event::Scalar(_, style, ..) => {
+                self.begin_value(line);
+                if style == TScalarStyle::Plain {
+                    self.plain.insert(self.path_string());
+                }
+                self.end_value();
+            }
+            Event::Alias(_) => {
+                self.begin_value(line);
+                self.end_value();
+            }
+            Event::MappingStart(..) => {
+                self.begin_value(line);
+                self.nodes.push(Node::Mapping(None));
+            }
+            Event::SequenceStart(..) => {
+                self.begin_value(line);
+                self.nodes.push(Node::Sequence(0));
+            }
+            Event::MappingEnd | Event::SequenceEnd => {
+                self.nodes.pop();
+                self.end_value();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::TestHarness;
+
+    const CONFIG: &str = r#"name: Test
+prompts:
+  - id: hello
+    prompt: Say hello
+    eval_path: ./missing
+    test_harness: !cargo
+      test_threds: 1
+  - id: hello
+    prompt: Say hello again
+    eval_path: .
+    test_harness: !pytest { report: { format: tap, path: report.tap } }
+agents:
+  - tool: claude_code
+    model: claude_sonnet_4
+  - tool: claude_code
+    model: claude_sonnet_4
+    iteration: 5
+"#;
+
+    fn write_config(dir: &Path, name: &str, yaml: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, yaml).unwrap();
+        path
+    }
+
+    fn issue(file: &Path, line: usize, path: &str, message: &str) -> ConfigIssue {
+        ConfigIssue {
+            file: file.to_path_buf(),
+            line: Some(line),
+            path: path.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_reports_every_issue_with_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = write_config(dir.path(), "eval-config.yaml", CONFIG);
+        let issues = check_config(&file, false).unwrap_err();
+
+        let missing = dir.path().join("missing");
+        assert_eq!(
+            issues,
+            vec![
+                issue(
+                    &file,
+                    7,
+                    "prompts.0.test_harness.test_threds",
+                    "unknown field `test_threds`"
+                ),
+                issue(&file, 17, "agents.1.iteration", "unknown field `iteration`"),
+                issue(
+                    &file,
+                    5,
+                    "prompts.0.eval_path",
+                    &format!("directory {:?} does not exist", missing)
+                ),
+                issue(&file, 8, "prompts.1.id", "duplicate prompt id `hello`"),
+                issue(
+                    &file,
+                    11,
+                    "prompts.1.test_harness.report.format",
+                    "pytest writes `junit` or `pytest_json` reports, not `tap`"
+                ),
+                issue(
+                    &file,
+                    15,
+                    "agents.1",
+                    "duplicate agent `claude-code-claude-sonnet-4-20250514`"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_fields_are_reported_with_a_type_error() {
+        let yaml = r#"name: Test
+descripton: typo
+prompts:
+  - id: hello
+    promt: Say hello
+    eval_path: .
+    test_harness: !cargo
+      test_threads: lots
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let file = write_config(dir.path(), "eval-config.yaml", yaml);
+        let issues = check_config(&file, false).unwrap_err();
+        let lines: Vec<_> = issues.iter().map(|i| (i.line, i.path.as_str())).collect();
+        assert_eq!(
+            lines,
+            vec![
+                (Some(2), "descripton"),
+                (Some(5), "prompts.0.promt"),
+                (Some(8), "prompts.0.test_harness.test_threads"),
+            ]
+        );
+        assert_eq!(issues[1].message, "unknown field `promt`");
+    }
+
+    #[test]
+    fn test_resolves_paths_against_config_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("hello")).unwrap();
+        let yaml = r#"name: Test
+prompts:
+  - id: hello
+    prompt: Say hello
+    eval_path: ${ANODE_EVAL_UNSET_TEST_DIR:-./hello}
+    test_harness: !cargo {}
+agents:
+  - tool: fake
+    model: claude_sonnet_4
+    fake:
+      fixtures: fixtures/hello
+"#;
+        let file = write_config(dir.path(), "eval-config.yaml", yaml);
+        let config = check_config(&file, true).unwrap();
+        assert_eq!(
+            config.prompts[0].eval_path.as_deref(),
+            Some(dir.path().join("hello").as_path())
+        );
+        assert_eq!(
+            config.agents[0].fake.as_ref().unwrap().fixtures,
+            dir.path().join("fixtures/hello")
+        );
+    }
+
+    #[test]
+    fn test_interpolation_problems_are_located() {
+        let yaml = r#"name: Test
+# ${ANODE_EVAL_UNSET_TEST_VAR} in a comment is left alone
+prompts:
+  - id: hello
+    prompt: Say hello
+    eval_path: .
+    test_harness: !cargo
+      test_threads: ${ANODE_EVAL_UNSET_TEST_VAR:-lots}
+    env:
+      PORT: 3000
+      DATA_DIR: ${ANODE_EVAL_UNSET_TEST_VAR}
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let file = write_config(dir.path(), "eval-config.yaml", yaml);
+        let issues = check_config(&file, false).unwrap_err();
+        let lines: Vec<_> = issues.iter().map(|i| (i.line, i.path.as_str())).collect();
+        assert_eq!(
+            lines,
+            vec![
+                (Some(11), "prompts.0.env.DATA_DIR"),
+                (Some(8), "prompts.0.test_harness.test_threads"),
+            ]
+        );
+        assert_eq!(
+            issues[1].message,
+            "prompts[0].test_harness.test_threads: invalid type: string \"lots\", expected u32"
+        );
+
+        let file = write_config(dir.path(), "eval-config.yaml", &yaml.replace("lots", "2"));
+        let issues = check_config(&file, false).unwrap_err();
+        assert_eq!(issues.len(), 1);
+        let yaml = yaml.replace(
+            "${ANODE_EVAL_UNSET_TEST_VAR}\n",
+            "'${ANODE_EVAL_UNSET_TEST_VAR:-}'\n",
+        );
+        let file = write_config(dir.path(), "eval-config.yaml", &yaml.replace("lots", "2"));
+        let config = check_config(&file, false).unwrap();
+        let prompt = &config.prompts[0];
+        assert_eq!(prompt.env["PORT"], "3000");
+        assert!(matches!(
+            prompt.test_harness,
+            TestHarness::Cargo {
+                test_threads: Some(2),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_includes_and_prompt_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let top = write_config(
+            dir.path(),
+            "all.yaml",
+            "name: All\ninclude: [\"suites/*/eval-config.yaml\"]\nagents:\n  - tool: claude_code\n    model: claude_opus_4_5\n",
+        );
+        std::fs::write(dir.path().join("PROMPT.md"), "Say hello\n").unwrap();
+        write_config(
+            dir.path(),
+            "suites/a/eval-config.yaml",
+            "name: A\nprompts:\n  - id: a\n    prompt_file: ../../PROMPT.md\n    eval_path: .\n    test_harness: !cargo {}\nagents:\n  - tool: codex\n    model: gpt_5\n",
+        );
+        let config = check_config(&top, false).unwrap();
+        assert_eq!(config.prompts.len(), 1);
+        assert_eq!(config.prompts[0].prompt, "Say hello\n");
+        assert_eq!(config.agents.len(), 1);
+        assert_eq!(config.agents[0].tool, crate::agents::AgentTool::ClaudeCode);
+
+        let b = write_config(
+            dir.path(),
+            "suites/b/eval-config.yaml",
+            "name: B\ninclude: [../../all.yaml]\nprompts:\n  - id: b\n    prompt_file: MISSING.md\n    eval_path: .\n    test_harness: !cargo {}\n",
+        );
+        let issues = check_config(&top, false).unwrap_err();
+        let paths: Vec<_> = issues
+            .iter()
+            .map(|i| (i.file.as_path(), i.line, i.path.as_str()))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                (b.as_path(), Some(5), "prompts.0.prompt_file"),
+                (b.as_path(), Some(2), "include.0"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_api_keys() {
+        let yaml = r#"name: Test
+prompts: []
+agents:
+  - tool: codex
+    model: gpt_5
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let file = write_config(dir.path(), "eval-config.yaml", yaml);
+        assert!(check_config(&file, false).is_ok());
+
+        let issues = check_config(&file, true).unwrap_err();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, Some(4));
+        assert!(issues[0].message.contains("OPENAI_API_KEY"));
+
+        let yaml = format!(
+            "{}    env:\n      OPENAI_API_KEY: sk-other\nsettings:\n  api_keys:\n    env_vars: [OPENAI_API_KEY]\n",
+            yaml
+        );
+        let file = write_config(dir.path(), "eval-config.yaml", &yaml);
+        let issues = check_config(&file, false).unwrap_err();
+        assert_eq!(
+            issues,
+            vec![issue(
+                &file,
+                7,
+                "agents.0.env.OPENAI_API_KEY",
+                "`OPENAI_API_KEY` is an API key in settings.api_keys"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_matrix_agents() {
+        let yaml = r#"name: Test
+prompts: []
+agents:
+  - tool: claude_code
+    model: claude_opus_4_5
+matrix:
+  tool: [claude_code, opencode]
+  model: [claude_opus_4_5]
+  iterations: [10, 50]
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let file = write_config(dir.path(), "eval-config.yaml", yaml);
+        let issues = check_config(&file, false).unwrap_err();
+        assert_eq!(
+            issues,
+            vec![issue(
+                &file,
+                6,
+                "matrix",
+                "duplicate agent `claude-code-claude-opus-4-5-20251101`"
+            )]
+        );
+
+        let file = write_config(dir.path(), "eval-config.yaml", &yaml.replace("10, ", ""));
+        let config = check_config(&file, false).unwrap();
+        assert_eq!(config.agents.len(), 3);
+        assert!(config.matrix.is_none());
+    }
+
+    #[test]
+    fn test_example_configs_are_valid() {
+        let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+        for config in [
+            "hello_world/eval-config.yaml",
+            "crm_api/eval-config.yaml",
+            "s3_storage/eval-config.yaml",
+            "s3_distributed/eval-config.yaml",
+            "eval-suite.yaml",
+        ] {
+            if let Err(e) = EvalConfig::load(examples.join(config), false) {
+                panic!("{}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! On-disk layout of evaluation results
+//!
+//! Every evaluation gets its own directory under the output directory, and an
+//! index lists them all:
+//!
+//! ```text
+//! <output_dir>/
+//!   index.json            one entry per evaluation, newest first
+//!   <eval_id>/
+//!     results.json        the full `EvaluationResults`
+//!     report.md           human-readable report
+//!     runs/<run_id>/      logs and artifacts of each run
+//! ```
+
+use super::EvaluationResults;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+const INDEX_FILE: &str = "index.json";
+const RESULTS_FILE: &str = "results.json";
+const REPORT_FILE: &str = "report.md";
+
+/// Summary of one evaluation in `index.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub eval_id: String,
+    pub name: String,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Number of runs
+    pub runs: u32,
+    pub overall_pass_rate: f64,
+    pub best_agent: Option<String>,
+}
+
+impl From<&EvaluationResults> for IndexEntry {
+    fn from(results: &EvaluationResults) -> Self {
+        Self {
+            eval_id: results.eval_id.clone(),
+            name: results.name.clone(),
+            started_at: results.started_at,
+            completed_at: results.completed_at,
+            runs: results.runs.len() as u32,
+            overall_pass_rate: results.summary.overall_pass_rate,
+            best_agent: results.summary.best_agent.clone(),
+        }
+    }
+}
+
+/// Contents of `index.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResultsIndex {
+    pub evals: Vec<IndexEntry>,
+}
+
+/// Paths of the results layout under an output directory
+#[derive(Debug, Clone)]
+pub struct ResultsLayout {
+    root: PathBuf,
+}
+
+impl ResultsLayout {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn index_file(&self) -> PathBuf {
+        self.root.join(INDEX_FILE)
+    }
+
+    pub fn eval_dir(&self, eval_id: &str) -> PathBuf {
+        self.root.join(eval_id)
+    }
+
+    pub fn results_file(&self, eval_id: &str) -> PathBuf {
+        self.eval_dir(eval_id).join(RESULTS_FILE)
+    }
+
+    pub fn report_file(&self, eval_id: &str) -> PathBuf {
+        self.eval_dir(eval_id).join(REPORT_FILE)
+    }
+
+    /// Directory for a run's logs and artifacts
+    pub fn run_dir(&self, eval_id: &str, run_id: &str) -> PathBuf {
+        self.eval_dir(eval_id).join("runs").join(run_id)
+    }
+
+    /// Write an evaluation's results, report and run logs, and add it to the
+    /// index. Returns the evaluation's directory.
+    pub fn save(&self, results: &EvaluationResults) -> Result<PathBuf> {
+        let eval_dir = self.eval_dir(&results.eval_id);
+        std::fs::create_dir_all(&eval_dir)
+            .with_context(|| format!("Failed to create {:?}", eval_dir))?;
+
+        results.save_json(&self.results_file(&results.eval_id))?;
+        std::fs::write(
+            self.report_file(&results.eval_id),
+            results.generate_report(),
+        )?;
+
+        for run in &results.runs {
+            let logs = [
+                ("agent.log", &run.agent_logs),
+                ("build.log", &run.build_output),
+                (
+                    "test-output.txt",
+                    &run.test_results.as_ref().map(|t| t.raw_output.clone()),
+                ),
+            ];
+            let run_dir = self.run_dir(&results.eval_id, &run.run_id);
+            for (name, content) in logs {
+                if let Some(content) = content {
+                    std::fs::create_dir_all(&run_dir)?;
+                    std::fs::write(run_dir.join(name), content)?;
+                }
+            }
+        }
+
+        self.update_index(results)?;
+        Ok(eval_dir)
+    }
+
+    /// Read `index.json`, or an empty index if there is none yet
+    pub fn read_index(&self) -> Result<ResultsIndex> {
+        let path = self.index_file();
+        if !path.exists() {
+            return Ok(ResultsIndex::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse {:?}", path))
+    }
+
+    fn update_index(&self, results: &EvaluationResults) -> Result<()> {
+        let mut index = self.read_index()?;
+        index.evals.retain(|e| e.eval_id != results.eval_id);
+        index.evals.push(IndexEntry::from(results));
+        index.evals.sort_by_key(|e| std::cmp::Reverse(e.started_at));
+        std::fs::write(self.index_file(), serde_json::to_string_pretty(&index)?)?;
+        Ok(())
+    }
+
+    /// Load the results of every evaluation directory, newest first.
+    /// Directories without readable results are skipped.
+    pub fn load_all(&self) -> Result<Vec<(PathBuf, EvaluationResults)>> {
+        let mut all = Vec::new();
+        if !self.root.exists() {
+            return Ok(all);
+        }
+
+        for entry in std::fs::read_dir(&self.root)? {
+            let path = entry?.path().join(RESULTS_FILE);
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            match serde_json::from_str::<EvaluationResults>(&content) {
+                Ok(results) => all.push((path, results)),
+                Err(e) => tracing::warn!("Skipping unreadable results {:?}: {}", path, e),
+            }
+        }
+
+        all.sort_by_key(|(_, r)| std::cmp::Reverse(r.started_at));
+        Ok(all)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::EvalRunResult;
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = ResultsLayout::new(dir.path());
+
+        let mut results = EvaluationResults::new("Test Eval", "eval-1");
+        let mut run = EvalRunResult::new("run-1", "prompt-1", "agent-1", "fake", "model");
+        run.agent_logs = Some("agent output".to_string());
+        run.fail_build("error[E0425]");
+        results.add_run(run);
+        results.finalize();
+
+        let eval_dir = layout.save(&results).unwrap();
+        assert_eq!(eval_dir, dir.path().join("eval-1"));
+        assert!(eval_dir.join("report.md").exists());
+        let run_dir = layout.run_dir("eval-1", "run-1");
+        assert_eq!(
+            std::fs::read_to_string(run_dir.join("agent.log")).unwrap(),
+            "agent output"
+        );
+        assert!(run_dir.join("build.log").exists());
+        assert!(!run_dir.join("test-output.txt").exists());
+
+        // Saving again replaces the index entry
+        let mut second = EvaluationResults::new("Second", "eval-2");
+        second.finalize();
+        layout.save(&second).unwrap();
+        layout.save(&results).unwrap();
+
+        let index = layout.read_index().unwrap();
+        let ids: Vec<&str> = index.evals.iter().map(|e| e.eval_id.as_str()).collect();
+        assert_eq!(ids, vec!["eval-2", "eval-1"]);
+        assert_eq!(index.evals[1].runs, 1);
+
+        let loaded = layout.load_all().unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[1].0, layout.results_file("eval-1"));
+    }
+}
@@ -1,43 +1,75 @@
-//! Local runner for testing without Kubernetes
-//! This simulates what would happen in a pod by running tests locally
+//! Local runner for running evaluations without Kubernetes
+//! Each run copies the eval into a scratch workspace, runs the agent CLI as a
+//! child process with the same arguments the pod entrypoint uses, then runs
+//! the tests in that workspace
 
 use crate::agents::AgentConfig;
-use crate::cli::{EvalConfig, PromptConfig, TestHarness};
-use crate::eval::{EvalRunResult, EvaluationResults, RunStatus, TestCaseResult, TestSuiteResult};
-use anyhow::Result;
-use std::path::Path;
-use std::process::Command;
+use crate::cli::{EvalConfig, GitRepoConfig, PromptConfig, TestHarness};
+use crate::eval::runner::record_agent_output;
+use crate::eval::{
+    parse_test_results, EvalRunResult, EvaluationResults, HarnessOutput, ResultsLayout, RunStatus,
+    TestSuiteResult, RESULTS_FILE_ENV,
+};
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+/// Directories that are never copied into a run workspace
+pub(crate) const SKIPPED_DIRS: &[&str] = &["target", ".git", "node_modules"];
+
 /// Local evaluation runner (no Kubernetes required)
 pub struct LocalEvalRunner {
     config: EvalConfig,
+    api_keys: BTreeMap<String, String>,
     results: Arc<Mutex<EvaluationResults>>,
+    workspace_root: PathBuf,
+    skip_agent: bool,
 }
 
 impl LocalEvalRunner {
-    pub fn new(config: EvalConfig) -> Self {
+    pub fn new(config: EvalConfig) -> Result<Self> {
+        let api_keys = config.settings.api_keys.resolve()?;
         let eval_id = Uuid::new_v4().to_string();
-        let results = Arc::new(Mutex::new(EvaluationResults::new(&config.name, &eval_id)));
+        let results = EvaluationResults::new(&config.name, &eval_id)
+            .with_filter(config.filter.clone())
+            .with_overrides(config.overrides.clone());
+        let results = Arc::new(Mutex::new(results));
+
+        Ok(Self {
+            config,
+            api_keys,
+            results,
+            workspace_root: std::env::temp_dir().join("anode-eval"),
+            skip_agent: false,
+        })
+    }
 
-        Self { config, results }
+    /// Only run the test harness against each prompt's eval_path, without agents
+    pub fn with_skip_agent(mut self, skip_agent: bool) -> Self {
+        self.skip_agent = skip_agent;
+        self
     }
 
-    /// Run evaluation locally (simulating what agents would do)
-    pub async fn run_local_tests(&self) -> Result<EvaluationResults> {
+    /// Run the evaluation locally
+    pub async fn run(&self, timeout_hours: u32) -> Result<EvaluationResults> {
         info!(
             "Starting LOCAL evaluation: {} (no Kubernetes)",
             self.config.name
         );
 
-        let combinations = self.config.combinations();
-        info!("Running {} combinations locally", combinations.len());
+        let runs = self.config.scheduled_runs();
+        info!("Running {} runs locally", runs.len());
 
-        for (prompt, agent) in combinations {
-            let result = self.run_single_local(&prompt, &agent).await;
+        for (prompt, agent, sample) in runs {
+            let result = self
+                .run_single_local(&prompt, &agent, sample, timeout_hours)
+                .await;
             let mut results = self.results.lock().await;
             results.add_run(result);
         }
@@ -48,56 +80,301 @@ impl LocalEvalRunner {
         Ok(final_results.clone())
     }
 
-    async fn run_single_local(&self, prompt: &PromptConfig, agent: &AgentConfig) -> EvalRunResult {
+    async fn run_single_local(
+        &self,
+        prompt: &PromptConfig,
+        agent: &AgentConfig,
+        sample: u32,
+        timeout_hours: u32,
+    ) -> EvalRunResult {
         let run_id = Uuid::new_v4().to_string();
         let agent_id = agent.id();
 
-        info!(
-            "[LOCAL] Running tests for prompt '{}' with agent '{}' (simulated)",
-            prompt.id, agent_id
-        );
-
         let mut result = EvalRunResult::new(
             &run_id,
             &prompt.id,
             &agent_id,
-            &agent.tool.to_string(),
+            &agent.tool_name(),
             &agent.model.to_string(),
-        );
+        )
+        .with_sample(sample);
+        if let Some(budget) = agent.total_turn_budget() {
+            result = result.with_max_iterations(budget);
+        }
         result.status = RunStatus::Running;
 
-        // Run the actual tests locally
-        match self.run_local_test_harness(&prompt.eval_path, &prompt.test_harness) {
-            Ok(test_results) => {
-                result.complete_with_results(test_results);
+        let workspace = self.workspace_root.join(&run_id);
+        let env = prompt.run_env(agent);
+
+        if self.skip_agent {
+            info!(
+                "[LOCAL] Running tests for prompt '{}' against {} (agent skipped)",
+                prompt.id,
+                prompt.source()
+            );
+            let tests = match &prompt.eval_path {
+                Some(eval_path) => {
+                    self.run_local_test_harness(eval_path, &prompt.test_harness, &env, true)
+                        .await
+                }
+                // Git sources have no directory to test in place
+                None => {
+                    let tests = match prepare_workspace(prompt, &env, &workspace) {
+                        Ok(()) => {
+                            self.run_local_test_harness(
+                                &workspace,
+                                &prompt.test_harness,
+                                &env,
+                                false,
+                            )
+                            .await
+                        }
+                        Err(e) => Err(e),
+                    };
+                    let _ = std::fs::remove_dir_all(&workspace);
+                    tests
+                }
+            };
+            record_test_results(&mut result, tests);
+            return result;
+        }
+
+        info!(
+            "[LOCAL] Starting run {} for prompt '{}' with agent '{}'",
+            run_id, prompt.id, agent_id
+        );
+
+        if let Err(e) = prepare_workspace(prompt, &env, &workspace) {
+            error!(
+                "[LOCAL] Failed to prepare workspace for run {}: {}",
+                run_id, e
+            );
+            result.fail_with_error(&format!("Failed to prepare workspace: {:#}", e));
+            return result;
+        }
+
+        // Agents that read the prompt from a file get it outside the workspace
+        let prompt_file = self.workspace_root.join(format!("{}-prompt.txt", run_id));
+        if let Err(e) = std::fs::write(&prompt_file, &prompt.prompt) {
+            error!(
+                "[LOCAL] Failed to write prompt file for run {}: {}",
+                run_id, e
+            );
+            result.fail_with_error(&format!("Failed to write prompt file: {}", e));
+            return result;
+        }
+
+        let timeout = prompt
+            .timeout_hours
+            .unwrap_or(self.config.settings.default_timeout_hours)
+            .min(timeout_hours);
+        let deadline = Instant::now() + Duration::from_secs(timeout as u64 * 3600);
+
+        // Single-shot agents run once; ralph-loop agents are re-invoked until
+        // the tests pass or the iteration budget is spent
+        let max_invocations = agent.max_invocations();
+        let mut agent_logs = String::new();
+        let mut test_outcome = None;
+
+        for iteration in 1..=max_invocations {
+            if max_invocations > 1 {
                 info!(
-                    "[LOCAL] Tests completed for {} with score {:.2}%",
-                    agent_id,
-                    result.score.unwrap_or(0.0)
+                    "[LOCAL] Agent iteration {}/{} for run {}",
+                    iteration, max_invocations, run_id
                 );
             }
-            Err(e) => {
-                error!("[LOCAL] Tests failed for {}: {}", agent_id, e);
-                result.fail_with_error(&format!("Test execution failed: {}", e));
+            result.set_iterations_used(iteration);
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self
+                .run_agent(
+                    agent,
+                    &prompt.prompt,
+                    &prompt_file,
+                    &workspace,
+                    &env,
+                    remaining,
+                )
+                .await
+            {
+                Ok(logs) => agent_logs.push_str(&logs),
+                Err(AgentRunError::TimedOut) => {
+                    warn!("[LOCAL] Agent timed out for run {}", run_id);
+                    result.agent_logs = Some(agent_logs.clone());
+                    result.fail_with_error(&format!("Agent timed out after {} hours", timeout));
+                    result.status = RunStatus::Timeout;
+                    break;
+                }
+                Err(AgentRunError::Spawn(e)) => {
+                    error!("[LOCAL] Failed to start agent for run {}: {}", run_id, e);
+                    result.fail_with_error(&format!("Failed to start agent: {}", e));
+                    break;
+                }
+            }
+
+            let tests = self
+                .run_local_test_harness(&workspace, &prompt.test_harness, &env, false)
+                .await;
+            let passed = matches!(&tests, Ok(t) if t.all_passed());
+            test_outcome = Some(tests);
+            if passed {
+                if iteration < max_invocations {
+                    info!(
+                        "[LOCAL] Tests passed after iteration {}, stopping early",
+                        iteration
+                    );
+                }
+                break;
             }
         }
 
+        if let Some(tests) = test_outcome.filter(|_| result.status == RunStatus::Running) {
+            record_agent_output(&mut result, agent, &agent_logs);
+            result.agent_logs = Some(agent_logs);
+            record_test_results(&mut result, tests);
+        }
+
+        if self.config.settings.cleanup_on_complete {
+            if let Err(e) = std::fs::remove_dir_all(&workspace) {
+                warn!(
+                    "[LOCAL] Failed to clean up workspace {:?}: {}",
+                    workspace, e
+                );
+            }
+            let _ = std::fs::remove_file(&prompt_file);
+        }
+
         result
     }
 
-    fn run_local_test_harness(
+    /// Run the agent CLI in the workspace and return its combined output
+    async fn run_agent(
+        &self,
+        agent: &AgentConfig,
+        prompt: &str,
+        prompt_file: &Path,
+        workspace: &Path,
+        env: &BTreeMap<String, String>,
+        max_duration: Duration,
+    ) -> std::result::Result<String, AgentRunError> {
+        let argv = agent
+            .adapter()
+            .map_err(AgentRunError::Spawn)?
+            .invocation(&agent.invocation(prompt, prompt_file));
+        let (program, args) = argv.split_first().expect("agent invocation is never empty");
+
+        info!("[LOCAL] Running agent: {} in {:?}", program, workspace);
+
+        let child = tokio::process::Command::new(program)
+            .args(args)
+            .current_dir(workspace)
+            .envs(&self.api_keys)
+            .envs(env)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            // The agent leads its own process group, so the processes it
+            // starts are killed with it on timeout
+            .process_group(0)
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| AgentRunError::Spawn(e.into()))?;
+        let pgid = child.id();
+
+        match tokio::time::timeout(max_duration, child.wait_with_output()).await {
+            Ok(Ok(output)) => {
+                let logs = format!(
+                    "{}\n{}",
+                    String::from_utf8_lossy(&output.stdout),
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                if !output.status.success() {
+                    warn!("[LOCAL] Agent exited with {}", output.status);
+                }
+                Ok(logs)
+            }
+            Ok(Err(e)) => Err(AgentRunError::Spawn(e.into())),
+            Err(_) => {
+                if let Some(pgid) = pgid {
+                    kill_process_group(pgid);
+                }
+                Err(AgentRunError::TimedOut)
+            }
+        }
+    }
+
+    /// Build and test the eval in `eval_path`, which is the user's own eval
+    /// directory when `in_place` rather than a run workspace
+    async fn run_local_test_harness(
         &self,
         eval_path: &Path,
         harness: &TestHarness,
+        env: &BTreeMap<String, String>,
+        in_place: bool,
     ) -> Result<TestSuiteResult> {
+        if let Some((cmd, args)) = harness.build_command() {
+            info!("[LOCAL] Building: {} {:?} in {:?}", cmd, args, eval_path);
+
+            let output = tokio::process::Command::new(&cmd)
+                .args(&args)
+                .current_dir(eval_path)
+                .envs(env)
+                .output()
+                .await?;
+            if !output.status.success() {
+                let mut build_output = format!(
+                    "{}\n{}",
+                    String::from_utf8_lossy(&output.stdout),
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                // coreutils `timeout` exits with 124 when it kills the command
+                if let (Some(124), Some(seconds)) =
+                    (output.status.code(), harness.timeout_seconds())
+                {
+                    build_output
+                        .push_str(&format!("\nBuild timed out after {} seconds\n", seconds));
+                }
+                return Err(BuildFailed {
+                    output: build_output,
+                }
+                .into());
+            }
+        }
+
+        // Outside the workspace, so it works for eval_paths tested in place,
+        // and per run, so concurrent runs don't read each other's results
+        let test_run_id = Uuid::new_v4();
+        let results_file = std::env::temp_dir().join(format!("anode-results-{}.json", test_run_id));
+        let default_report = std::env::temp_dir().join(format!("anode-report-{}", test_run_id));
+        let harness = &harness.with_default_report_path(&default_report);
+
+        // A report left over from an earlier run must not be scored. Files in
+        // the user's own eval directory are left alone, and only read if the
+        // tests rewrite them.
+        let report_path = harness.report().map(|report| eval_path.join(report.path));
+        let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        let stale_report = match &report_path {
+            Some(path) if in_place => modified(path),
+            Some(path) => {
+                let _ = std::fs::remove_file(path);
+                None
+            }
+            None => None,
+        };
+
         let (cmd, args) = harness.test_command();
 
         info!("[LOCAL] Running: {} {:?} in {:?}", cmd, args, eval_path);
 
-        let output = Command::new(&cmd)
+        let started = Instant::now();
+        let output = tokio::process::Command::new(&cmd)
             .args(&args)
             .current_dir(eval_path)
-            .output()?;
+            .envs(env)
+            .env(RESULTS_FILE_ENV, &results_file)
+            .output()
+            .await?;
+        let duration_ms = started.elapsed().as_millis() as u64;
 
         let stdout = String::from_utf8_lossy(&output.stdout);
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -105,163 +382,456 @@ impl LocalEvalRunner {
 
         info!("[LOCAL] Test output:\n{}", combined_output);
 
-        // Parse the output
-        parse_test_output(harness, &combined_output)
+        if let (Some(124), Some(seconds)) = (output.status.code(), harness.timeout_seconds()) {
+            let _ = std::fs::remove_file(&results_file);
+            let _ = std::fs::remove_file(&default_report);
+            return Err(TestsTimedOut { seconds }.into());
+        }
+
+        let output = HarnessOutput {
+            console: combined_output,
+            results_file: std::fs::read_to_string(&results_file).ok(),
+            report: report_path
+                .filter(|path| stale_report.is_none() || modified(path) != stale_report)
+                .and_then(|path| std::fs::read_to_string(path).ok()),
+            duration_ms: Some(duration_ms),
+        };
+        let _ = std::fs::remove_file(&results_file);
+        let _ = std::fs::remove_file(&default_report);
+        parse_test_results(harness, &output)
     }
 
+    #[allow(dead_code)]
     pub async fn results(&self) -> EvaluationResults {
         self.results.lock().await.clone()
     }
 
-    pub async fn save_results(&self, output_dir: &Path) -> Result<()> {
-        std::fs::create_dir_all(output_dir)?;
-
+    /// Save results to the output directory (see [`ResultsLayout`]),
+    /// returning the evaluation's directory
+    pub async fn save_results(&self, output_dir: &Path) -> Result<PathBuf> {
         let results = self.results.lock().await;
+        let eval_dir = ResultsLayout::new(output_dir).save(&results)?;
+        info!("Saved results to {:?}", eval_dir);
+        Ok(eval_dir)
+    }
+}
 
-        let json_path = output_dir.join(format!("{}.json", results.eval_id));
-        results.save_json(&json_path)?;
-        info!("Saved results to {:?}", json_path);
+/// Record the outcome of a test harness run on `result`
+fn record_test_results(result: &mut EvalRunResult, tests: Result<TestSuiteResult>) {
+    match tests {
+        Ok(test_results) => {
+            result.complete_with_results(test_results);
+            info!(
+                "[LOCAL] Tests completed for {} with score {:.2}%",
+                result.agent_id,
+                result.score.unwrap_or(0.0)
+            );
+        }
+        Err(e) => {
+            if let Some(build) = e.downcast_ref::<BuildFailed>() {
+                warn!("[LOCAL] Build failed for {}", result.agent_id);
+                result.fail_build(&build.output);
+            } else if let Some(timed_out) = e.downcast_ref::<TestsTimedOut>() {
+                warn!("[LOCAL] Tests timed out for {}", result.agent_id);
+                result.fail_with_error(&timed_out.to_string());
+                result.status = RunStatus::Timeout;
+            } else {
+                error!("[LOCAL] Tests failed for {}: {}", result.agent_id, e);
+                result.fail_with_error(&format!("Test execution failed: {}", e));
+            }
+        }
+    }
+}
+
+/// The harness's build step failed, so no tests ran
+#[derive(Debug, thiserror::Error)]
+#[error("build failed")]
+struct BuildFailed {
+    output: String,
+}
 
-        let report_path = output_dir.join(format!("{}_report.md", results.eval_id));
-        let report = results.generate_report();
-        std::fs::write(&report_path, report)?;
-        info!("Saved report to {:?}", report_path);
+/// The harness's time limit killed the tests
+#[derive(Debug, thiserror::Error)]
+#[error("Tests timed out after {seconds} seconds")]
+struct TestsTimedOut {
+    seconds: u64,
+}
 
-        Ok(())
-    }
+/// Why an agent process did not produce output
+enum AgentRunError {
+    /// The agent could not be started or waited on
+    Spawn(anyhow::Error),
+    /// The agent exceeded its timeout and was killed
+    TimedOut,
 }
 
-fn parse_test_output(harness: &TestHarness, output: &str) -> Result<TestSuiteResult> {
-    match harness {
-        TestHarness::Cargo { .. } => parse_cargo_test_output(output),
-        TestHarness::Npm { .. } => parse_generic_test_output(output),
-        TestHarness::Pytest { .. } => parse_pytest_output(output),
-        TestHarness::Go { .. } => parse_go_test_output(output),
-        TestHarness::Custom { .. } => parse_generic_test_output(output),
+/// Kill every process in the group `pgid` with SIGKILL
+fn kill_process_group(pgid: u32) {
+    let group = format!("-{}", pgid);
+    if let Err(e) = Command::new("kill").args(["-KILL", "--", &group]).status() {
+        warn!("[LOCAL] Failed to kill process group {}: {}", pgid, e);
     }
 }
 
-fn parse_cargo_test_output(output: &str) -> Result<TestSuiteResult> {
-    let mut tests = Vec::new();
-    let mut total = 0;
-    let mut passed = 0;
-    let mut failed = 0;
-
-    for line in output.lines() {
-        if line.starts_with("test ") && (line.contains(" ... ok") || line.contains(" ... FAILED")) {
-            total += 1;
-            let test_passed = line.contains(" ... ok");
-            if test_passed {
-                passed += 1;
-            } else {
-                failed += 1;
-            }
+/// Copy the eval (or a checkout of its git repo) into a fresh workspace and
+/// run the prompt's setup commands with `env`
+fn prepare_workspace(
+    prompt: &PromptConfig,
+    env: &BTreeMap<String, String>,
+    workspace: &Path,
+) -> Result<()> {
+    if workspace.exists() {
+        std::fs::remove_dir_all(workspace)?;
+    }
+    std::fs::create_dir_all(workspace)?;
+
+    if let Some(repo) = &prompt.git_repo {
+        checkout_git_repo(repo, workspace)
+            .context(format!("Failed to check out {} into workspace", repo))?;
+    } else if let Some(eval_path) = &prompt.eval_path {
+        copy_dir(eval_path, workspace)
+            .context(format!("Failed to copy {:?} into workspace", eval_path))?;
+    }
 
-            let name = line
-                .strip_prefix("test ")
-                .and_then(|s| s.split(" ... ").next())
-                .unwrap_or("unknown")
-                .to_string();
-
-            tests.push(TestCaseResult {
-                name,
-                passed: test_passed,
-                duration_ms: None,
-                error: None,
-                stdout: None,
-            });
+    for setup in &prompt.setup_commands {
+        info!("[LOCAL] Running setup command: {}", setup);
+        let status = Command::new("bash")
+            .args(["-c", setup])
+            .current_dir(workspace)
+            .envs(env)
+            .status()?;
+        if !status.success() {
+            anyhow::bail!("Setup command failed with {}: {}", status, setup);
         }
     }
 
-    Ok(TestSuiteResult {
-        total,
-        passed,
-        failed,
-        skipped: 0,
-        tests,
-        duration_ms: 0,
-        raw_output: output.to_string(),
-    })
+    Ok(())
 }
 
-fn parse_pytest_output(output: &str) -> Result<TestSuiteResult> {
-    let mut total = 0;
-    let mut passed = 0;
-    let mut failed = 0;
-
-    for line in output.lines() {
-        if line.contains("PASSED") {
-            total += 1;
-            passed += 1;
-        } else if line.contains("FAILED") {
-            total += 1;
-            failed += 1;
-        }
+/// Clone a git repo next to the workspace, check out the configured ref and
+/// copy the configured subdirectory into the workspace
+fn checkout_git_repo(repo: &GitRepoConfig, workspace: &Path) -> Result<()> {
+    let clone_dir = workspace.with_extension("repo");
+    if clone_dir.exists() {
+        std::fs::remove_dir_all(&clone_dir)?;
     }
 
-    Ok(TestSuiteResult {
-        total,
-        passed,
-        failed,
-        skipped: 0,
-        tests: vec![],
-        duration_ms: 0,
-        raw_output: output.to_string(),
+    let git = |args: &[&std::ffi::OsStr]| -> Result<()> {
+        let output = Command::new("git").args(args).output()?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "git {:?} failed: {}",
+                args,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    };
+
+    info!("[LOCAL] Cloning {}", repo);
+    let result = git(&[
+        "clone".as_ref(),
+        "--quiet".as_ref(),
+        repo.url.as_ref(),
+        clone_dir.as_ref(),
+    ])
+    .and_then(|_| match &repo.git_ref {
+        Some(git_ref) => git(&[
+            "-C".as_ref(),
+            clone_dir.as_ref(),
+            "checkout".as_ref(),
+            "--quiet".as_ref(),
+            git_ref.as_ref(),
+        ]),
+        None => Ok(()),
     })
+    .and_then(|_| {
+        let src = match &repo.subdir {
+            Some(subdir) => clone_dir.join(subdir),
+            None => clone_dir.clone(),
+        };
+        if !src.is_dir() {
+            anyhow::bail!("{:?} is not a directory in the repository", repo.subdir);
+        }
+        copy_dir(&src, workspace)
+    });
+
+    let _ = std::fs::remove_dir_all(&clone_dir);
+    result
 }
 
-fn parse_go_test_output(output: &str) -> Result<TestSuiteResult> {
-    let mut total = 0;
-    let mut passed = 0;
-    let mut failed = 0;
-
-    for line in output.lines() {
-        if line.starts_with("--- PASS:") {
-            total += 1;
-            passed += 1;
-        } else if line.starts_with("--- FAIL:") {
-            total += 1;
-            failed += 1;
+/// Recursively copy `src` into `dst`, skipping build output and VCS directories
+fn copy_dir(src: &Path, dst: &Path) -> Result<()> {
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let path = entry.path();
+        let target = dst.join(entry.file_name());
+
+        if path.is_dir() {
+            if SKIPPED_DIRS.iter().any(|d| entry.file_name() == *d) {
+                continue;
+            }
+            std::fs::create_dir_all(&target)?;
+            copy_dir(&path, &target)?;
+        } else {
+            std::fs::copy(&path, &target)?;
         }
     }
-
-    Ok(TestSuiteResult {
-        total,
-        passed,
-        failed,
-        skipped: 0,
-        tests: vec![],
-        duration_ms: 0,
-        raw_output: output.to_string(),
-    })
+    Ok(())
 }
 
-fn parse_generic_test_output(output: &str) -> Result<TestSuiteResult> {
-    let mut total = 0;
-    let mut passed = 0;
-    let mut failed = 0;
-
-    for line in output.lines() {
-        let line_lower = line.to_lowercase();
-        if line_lower.contains("passed") && line_lower.contains("failed") {
-            let parts: Vec<&str> = line.split(|c: char| !c.is_numeric()).collect();
-            let nums: Vec<u32> = parts.iter().filter_map(|s| s.parse().ok()).collect();
-            if nums.len() >= 2 {
-                passed = nums[0];
-                failed = nums[1];
-                total = passed + failed;
-                break;
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::{CustomAgentConfig, ModelVersion};
+
+    #[test]
+    fn test_copy_dir_skips_build_output() {
+        let src = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(src.path().join("src")).unwrap();
+        std::fs::create_dir_all(src.path().join("target/debug")).unwrap();
+        std::fs::write(src.path().join("Cargo.toml"), "[package]").unwrap();
+        std::fs::write(src.path().join("src/lib.rs"), "").unwrap();
+        std::fs::write(src.path().join("target/debug/out"), "").unwrap();
+
+        let dst = tempfile::tempdir().unwrap();
+        copy_dir(src.path(), dst.path()).unwrap();
+
+        assert!(dst.path().join("Cargo.toml").exists());
+        assert!(dst.path().join("src/lib.rs").exists());
+        assert!(!dst.path().join("target").exists());
+    }
+
+    #[tokio::test]
+    async fn test_build_failure_is_recorded_separately() {
+        let runner = LocalEvalRunner::new(EvalConfig::sample()).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let harness = TestHarness::Shell {
+            build_command: Some("echo 'error: expected one of'; exit 101".to_string()),
+            test_command: "echo 'test result: ok. 1 passed; 0 failed'".to_string(),
+            timeout_seconds: Some(60),
+            report: None,
+        };
+
+        let mut result = EvalRunResult::new("run", "prompt", "agent", "tool", "model");
+        record_test_results(
+            &mut result,
+            runner
+                .run_local_test_harness(dir.path(), &harness, &BTreeMap::new(), false)
+                .await,
+        );
+        assert_eq!(result.status, RunStatus::BuildFailed);
+        assert!(result
+            .build_output
+            .unwrap()
+            .contains("error: expected one of"));
+        assert!(result.test_results.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_agent_is_killed_with_its_children() {
+        let runner = LocalEvalRunner::new(EvalConfig::sample()).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let custom = CustomAgentConfig {
+            name: "sleeper".to_string(),
+            install: None,
+            command: "sleep 30 & echo $! > sleep.pid; wait".to_string(),
+            env: vec![],
+            output_parser: None,
+        };
+        let agent = AgentConfig::custom(custom, ModelVersion::new("gpt_5"));
+
+        let outcome = runner
+            .run_agent(
+                &agent,
+                "",
+                &dir.path().join("prompt.txt"),
+                dir.path(),
+                &BTreeMap::new(),
+                Duration::from_secs(1),
+            )
+            .await;
+        assert!(matches!(outcome, Err(AgentRunError::TimedOut)));
+
+        // The background `sleep` is gone (or a zombie awaiting its reaper)
+        let pid = std::fs::read_to_string(dir.path().join("sleep.pid")).unwrap();
+        let alive = || {
+            let ps = Command::new("ps")
+                .args(["-o", "stat=", "-p", pid.trim()])
+                .output()
+                .unwrap();
+            let stat = String::from_utf8_lossy(&ps.stdout);
+            !stat.trim().is_empty() && !stat.trim().starts_with('Z')
+        };
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while alive() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
         }
+        assert!(!alive(), "sleep {} outlived the agent", pid.trim());
     }
 
-    Ok(TestSuiteResult {
-        total,
-        passed,
-        failed,
-        skipped: 0,
-        tests: vec![],
-        duration_ms: 0,
-        raw_output: output.to_string(),
-    })
+    #[tokio::test]
+    async fn test_timed_out_tests_are_recorded_as_timeout() {
+        let runner = LocalEvalRunner::new(EvalConfig::sample()).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let harness = TestHarness::Custom {
+            command: "sleep".to_string(),
+            args: vec!["10".to_string()],
+            timeout_seconds: Some(1),
+            report: None,
+        };
+
+        let mut result = EvalRunResult::new("run", "prompt", "agent", "tool", "model");
+        record_test_results(
+            &mut result,
+            runner
+                .run_local_test_harness(dir.path(), &harness, &BTreeMap::new(), false)
+                .await,
+        );
+        assert_eq!(result.status, RunStatus::Timeout);
+        assert_eq!(
+            result.error.as_deref(),
+            Some("Tests timed out after 1 seconds")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_results_file_is_preferred_over_console_output() {
+        let runner = LocalEvalRunner::new(EvalConfig::sample()).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let harness = TestHarness::Shell {
+            build_command: None,
+            test_command: r#"echo '3 passed, 0 failed'
+echo '{"tests": [{"name": "a", "status": "passed"}, {"name": "b", "status": "failed", "message": "boom"}]}' > "$ANODE_RESULTS_FILE""#
+                .to_string(),
+            timeout_seconds: None,
+            report: None,
+        };
+
+        let results = runner
+            .run_local_test_harness(dir.path(), &harness, &BTreeMap::new(), false)
+            .await
+            .unwrap();
+        assert_eq!((results.total, results.passed), (2, 1));
+        assert_eq!(results.tests[1].error.as_deref(), Some("boom"));
+        assert!(results.raw_output.contains("3 passed"));
+    }
+
+    #[tokio::test]
+    async fn test_reports_are_only_cleared_in_run_workspaces() {
+        let runner = LocalEvalRunner::new(EvalConfig::sample()).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let report = dir.path().join("report.xml");
+        let harness: TestHarness = serde_yaml::from_str(
+            "!shell\ntest_command: echo '1 passed'\nreport:\n  format: junit\n  path: report.xml",
+        )
+        .unwrap();
+
+        // In place, a report the tests didn't rewrite is kept but not scored
+        let stale = r#"<testsuite><testcase name="old"><failure/></testcase></testsuite>"#;
+        std::fs::write(&report, stale).unwrap();
+        let results = runner
+            .run_local_test_harness(dir.path(), &harness, &BTreeMap::new(), true)
+            .await
+            .unwrap();
+        assert!(report.exists());
+        assert_eq!(results.failed, 0);
+        assert!(results.tests.is_empty());
+
+        // A report the tests rewrite is
+        let rewriting: TestHarness = serde_yaml::from_str(
+            "!shell\ntest_command: echo '<testsuite><testcase name=\"new\"/></testsuite>' > report.xml\nreport:\n  format: junit\n  path: report.xml",
+        )
+        .unwrap();
+        let results = runner
+            .run_local_test_harness(dir.path(), &rewriting, &BTreeMap::new(), true)
+            .await
+            .unwrap();
+        assert_eq!(results.tests[0].name, "new");
+
+        runner
+            .run_local_test_harness(dir.path(), &harness, &BTreeMap::new(), false)
+            .await
+            .unwrap();
+        assert!(!report.exists());
+    }
+
+    fn prompt(eval_path: Option<&Path>, git_repo: Option<GitRepoConfig>) -> PromptConfig {
+        PromptConfig {
+            id: "test".to_string(),
+            prompt: "Do it".to_string(),
+            prompt_file: None,
+            eval_path: eval_path.map(Path::to_path_buf),
+            git_repo,
+            test_harness: TestHarness::Custom {
+                command: "true".to_string(),
+                args: vec![],
+                timeout_seconds: None,
+                report: None,
+            },
+            setup_commands: vec![],
+            timeout_hours: None,
+            env: BTreeMap::new(),
+            tags: vec![],
+            samples: None,
+        }
+    }
+
+    #[test]
+    fn test_prepare_workspace_runs_setup_commands() {
+        let src = tempfile::tempdir().unwrap();
+        std::fs::write(src.path().join("README.md"), "eval").unwrap();
+
+        let root = tempfile::tempdir().unwrap();
+        let workspace = root.path().join("run");
+        let mut prompt = prompt(Some(src.path()), None);
+        prompt.setup_commands = vec!["echo $STEP > setup.txt".to_string()];
+        let env = BTreeMap::from([("STEP".to_string(), "setup".to_string())]);
+        prepare_workspace(&prompt, &env, &workspace).unwrap();
+
+        assert!(workspace.join("README.md").exists());
+        assert_eq!(
+            std::fs::read_to_string(workspace.join("setup.txt")).unwrap(),
+            "setup\n"
+        );
+    }
+
+    #[test]
+    fn test_prepare_workspace_from_git_repo() {
+        let repo = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(repo.path())
+                .stdout(Stdio::null())
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {:?} failed", args);
+        };
+        git(&["init", "--quiet"]);
+        std::fs::create_dir_all(repo.path().join("evals/hello")).unwrap();
+        std::fs::write(repo.path().join("evals/hello/lib.rs"), "v1").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "--quiet", "-m", "v1"]);
+        git(&["tag", "v1"]);
+        std::fs::write(repo.path().join("evals/hello/lib.rs"), "v2").unwrap();
+        git(&["commit", "--quiet", "-am", "v2"]);
+
+        let root = tempfile::tempdir().unwrap();
+        let workspace = root.path().join("run");
+        let source = GitRepoConfig {
+            url: format!("file://{}", repo.path().display()),
+            git_ref: Some("v1".to_string()),
+            subdir: Some(PathBuf::from("evals/hello")),
+        };
+        prepare_workspace(&prompt(None, Some(source)), &BTreeMap::new(), &workspace).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(workspace.join("lib.rs")).unwrap(),
+            "v1"
+        );
+        assert!(!workspace.join(".git").exists());
+        assert!(!workspace.with_extension("repo").exists());
+    }
 }
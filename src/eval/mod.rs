@@ -1,7 +1,11 @@
 mod runner;
 mod results;
 mod local_runner;
+mod layout;
+mod parsers;
 
 pub use runner::*;
 pub use results::*;
 pub use local_runner::*;
+pub use layout::*;
+pub use parsers::*;
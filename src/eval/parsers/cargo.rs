@@ -0,0 +1,315 @@
+use super::{TestCaseResult, TestOutputParser, TestSuiteResult};
+use anyhow::Result;
+
+/// Parser for `cargo test` output, either libtest's JSON events (nightly
+/// `--format=json`) or its plain text lines
+pub struct CargoTestParser;
+
+impl TestOutputParser for CargoTestParser {
+    fn parse(&self, output: &str) -> Result<TestSuiteResult> {
+        let mut tests = Vec::new();
+        let mut skipped = 0;
+
+        for line in output.lines() {
+            let Ok(json) = serde_json::from_str::<serde_json::Value>(line) else {
+                continue;
+            };
+            if json.get("type").and_then(|v| v.as_str()) != Some("test") {
+                continue;
+            }
+            match json.get("event").and_then(|v| v.as_str()) {
+                Some("started") | None => {}
+                Some("ignored") => skipped += 1,
+                Some(event) => tests.extend(parse_json_event(&json, event)),
+            }
+        }
+
+        // If JSON parsing didn't work, try plain text parsing
+        if tests.is_empty() && skipped == 0 {
+            return parse_plain(output);
+        }
+
+        Ok(super::suite_from_tests(tests, skipped, output))
+    }
+}
+
+/// Parse a libtest JSON event for a finished test
+fn parse_json_event(json: &serde_json::Value, event: &str) -> Option<TestCaseResult> {
+    let name = json.get("name").and_then(|v| v.as_str())?;
+    let passed = event == "ok";
+    let stdout = json
+        .get("stdout")
+        .and_then(|v| v.as_str())
+        .map(String::from);
+
+    Some(TestCaseResult {
+        name: name.to_string(),
+        passed,
+        duration_ms: json
+            .get("exec_time")
+            .and_then(|v| v.as_f64())
+            .map(|t| (t * 1000.0) as u64),
+        error: if passed { None } else { stdout.clone() },
+        stdout,
+        weight: None,
+        group: None,
+    })
+}
+
+/// Parse cargo test plain text output. Each test binary prints a
+/// `running N tests` section with a line per test, a `---- name stdout ----`
+/// block per failure and a `test result:` summary.
+fn parse_plain(output: &str) -> Result<TestSuiteResult> {
+    let mut tests: Vec<TestCaseResult> = Vec::new();
+    let mut skipped = 0;
+    let mut summary: Option<Summary> = None;
+    // Index of the current binary's first test, so failure blocks are
+    // matched within their own binary
+    let mut section_start = 0;
+    // Failure block being read: test name and lines
+    let mut block: Option<(&str, Vec<&str>)> = None;
+
+    for line in output.lines() {
+        let header = line
+            .strip_prefix("---- ")
+            .and_then(|rest| rest.strip_suffix(" stdout ----"));
+        let ends_block =
+            header.is_some() || line == "failures:" || line.starts_with("test result:");
+        if ends_block {
+            if let Some((name, lines)) = block.take() {
+                record_failure(&mut tests[section_start..], name, &lines);
+            }
+        }
+        if let Some(name) = header {
+            block = Some((name, Vec::new()));
+            continue;
+        }
+        if let Some((_, lines)) = block.as_mut() {
+            lines.push(line);
+            continue;
+        }
+
+        if line.starts_with("running ") && (line.ends_with(" tests") || line.ends_with(" test")) {
+            section_start = tests.len();
+        } else if let Some(counts) = Summary::parse(line) {
+            summary.get_or_insert_with(Summary::default).add(&counts);
+        } else if let Some((name, outcome)) = line
+            .strip_prefix("test ")
+            .and_then(|rest| rest.split_once(" ... "))
+        {
+            match outcome.trim() {
+                "ok" | "FAILED" => tests.push(TestCaseResult {
+                    name: name.to_string(),
+                    passed: outcome.trim() == "ok",
+                    duration_ms: None,
+                    error: None,
+                    stdout: None,
+                    weight: None,
+                    group: None,
+                }),
+                outcome if outcome.starts_with("ignored") => skipped += 1,
+                _ => {}
+            }
+        }
+    }
+
+    let mut results = super::suite_from_tests(tests, skipped, output);
+    // The summaries are exact even when test output interleaves with the
+    // `test ... ok` lines. Ignored tests didn't run, so they aren't in the
+    // total.
+    if let Some(summary) = summary {
+        results.passed = summary.passed;
+        results.failed = summary.failed;
+        results.skipped = summary.ignored;
+        results.total = summary.passed + summary.failed;
+        results.duration_ms = summary.duration_ms;
+    }
+    Ok(results)
+}
+
+/// Attach a failure block's output to the failed test it belongs to
+fn record_failure(tests: &mut [TestCaseResult], name: &str, lines: &[&str]) {
+    let Some(test) = tests.iter_mut().rev().find(|t| t.name == name && !t.passed) else {
+        return;
+    };
+    let stdout = lines.join("\n").trim().to_string();
+    // The panic message, without the hint about backtraces
+    let error = match lines.iter().position(|l| l.contains("panicked at")) {
+        Some(start) => lines[start..]
+            .iter()
+            .filter(|l| !l.starts_with("note: run with `RUST_BACKTRACE"))
+            .copied()
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim()
+            .to_string(),
+        None => stdout.clone(),
+    };
+    test.error = Some(error);
+    test.stdout = Some(stdout);
+}
+
+/// Counts from `test result:` lines
+#[derive(Debug, Default)]
+struct Summary {
+    passed: u32,
+    failed: u32,
+    ignored: u32,
+    duration_ms: u64,
+}
+
+impl Summary {
+    /// Parse `test result: FAILED. 1 passed; 1 failed; 0 ignored; 0 measured;
+    /// 0 filtered out; finished in 0.25s`
+    fn parse(line: &str) -> Option<Self> {
+        let rest = line.strip_prefix("test result: ")?;
+        let mut summary = Summary::default();
+        for part in rest.split(';') {
+            let words: Vec<&str> = part.split_whitespace().collect();
+            match words.as_slice() {
+                [.., n, "passed"] => summary.passed = n.parse().ok()?,
+                [n, "failed"] => summary.failed = n.parse().ok()?,
+                [n, "ignored"] => summary.ignored = n.parse().ok()?,
+                ["finished", "in", seconds] => {
+                    let seconds: f64 = seconds.trim_end_matches('s').parse().ok()?;
+                    summary.duration_ms = (seconds * 1000.0) as u64;
+                }
+                _ => {}
+            }
+        }
+        Some(summary)
+    }
+
+    fn add(&mut self, other: &Summary) {
+        self.passed += other.passed;
+        self.failed += other.failed;
+        self.ignored += other.ignored;
+        self.duration_ms += other.duration_ms;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cargo_test_plain() {
+        let output = r#"
+running 3 tests
+test tests::test_one ... ok
+test tests::test_two ... ok
+test tests::test_three ... FAILED
+
+failures:
+
+failures:
+    tests::test_three
+
+test result: FAILED. 2 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out
+"#;
+
+        let result = CargoTestParser.parse(output).unwrap();
+        assert_eq!(result.total, 3);
+        assert_eq!(result.passed, 2);
+        assert_eq!(result.failed, 1);
+    }
+
+    #[test]
+    fn test_parse_cargo_test_ignored() {
+        let output = r#"
+running 3 tests
+test tests::test_one ... ok
+test tests::test_slow ... ignored
+test tests::test_two ... ok
+
+test result: ok. 2 passed; 0 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.01s
+"#;
+
+        let result = CargoTestParser.parse(output).unwrap();
+        assert_eq!(
+            (result.total, result.passed, result.failed, result.skipped),
+            (2, 2, 0, 1)
+        );
+        assert_eq!(result.pass_rate(), 100.0);
+    }
+
+    #[test]
+    fn test_parse_cargo_test_failures_across_binaries() {
+        let output = r#"
+     Running unittests src/lib.rs (target/debug/deps/kv-1a2b)
+
+running 3 tests
+test tests::get ... ok
+test tests::put ... FAILED
+test tests::slow ... ignored
+
+failures:
+
+---- tests::put stdout ----
+writing key a
+thread 'tests::put' panicked at src/lib.rs:20:9:
+assertion `left == right` failed
+  left: 1
+ right: 0
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+
+failures:
+    tests::put
+
+test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.25s
+
+     Running tests/api.rs (target/debug/deps/api-3c4d)
+
+running 2 tests
+test put ... FAILED
+test list ... ok
+
+failures:
+
+---- put stdout ----
+thread 'put' panicked at tests/api.rs:5:5:
+not implemented
+
+failures:
+    put
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out; finished in 1.50s
+"#;
+
+        let result = CargoTestParser.parse(output).unwrap();
+        assert_eq!(
+            (result.total, result.passed, result.failed, result.skipped),
+            (4, 2, 2, 1)
+        );
+        assert_eq!(result.duration_ms, 1750);
+        assert_eq!(
+            result.tests[1].error.as_deref(),
+            Some("thread 'tests::put' panicked at src/lib.rs:20:9:\nassertion `left == right` failed\n  left: 1\n right: 0")
+        );
+        assert!(result.tests[1]
+            .stdout
+            .as_deref()
+            .unwrap()
+            .starts_with("writing key a\n"));
+        assert_eq!(result.tests[2].name, "put");
+        assert_eq!(
+            result.tests[2].error.as_deref(),
+            Some("thread 'put' panicked at tests/api.rs:5:5:\nnot implemented")
+        );
+    }
+
+    #[test]
+    fn test_parse_cargo_test_json() {
+        let output = r#"{ "type": "suite", "event": "started", "test_count": 2 }
+{ "type": "test", "event": "started", "name": "tests::one" }
+{ "type": "test", "event": "ok", "name": "tests::one", "exec_time": 0.25 }
+{ "type": "test", "event": "failed", "name": "tests::two", "stdout": "assertion failed" }
+"#;
+
+        let result = CargoTestParser.parse(output).unwrap();
+        assert_eq!((result.total, result.passed, result.failed), (2, 1, 1));
+        assert_eq!(result.tests[0].duration_ms, Some(250));
+        assert_eq!(result.tests[1].error.as_deref(), Some("assertion failed"));
+    }
+}
@@ -0,0 +1,186 @@
+use super::{TestOutputParser, TestSuiteResult};
+use anyhow::Result;
+
+/// Parser that looks for a summary line with pass and fail counts, for
+/// harnesses whose output format is unknown
+///
+/// Each count is read with the word after it, so the order doesn't matter:
+/// `4 passed, 1 failed` (pytest, cargo), `Tests: 1 failed, 4 passed, 5 total`
+/// (Jest) and `Tests 1 failed | 4 passed` (Vitest) all work. Mocha's counts,
+/// one per line (`4 passing`, `1 failing`), are combined. A log line that
+/// happens to hold a count (`1 failed attempt, retrying`) is not a summary.
+pub struct GenericParser;
+
+/// Counts found on a summary line
+#[derive(Debug, Default, Clone, Copy)]
+struct Counts {
+    passed: Option<u32>,
+    failed: Option<u32>,
+    skipped: Option<u32>,
+    total: Option<u32>,
+    /// Whether a count was Mocha's `passing` or `failing`
+    mocha: bool,
+}
+
+impl Counts {
+    fn from_line(line: &str) -> Self {
+        let mut counts = Counts::default();
+        let words: Vec<String> = line
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+
+        for pair in words.windows(2) {
+            let Ok(n) = pair[0].parse::<u32>() else {
+                continue;
+            };
+            counts.mocha |= matches!(pair[1].as_str(), "passing" | "failing");
+            let slot = match pair[1].as_str() {
+                "passed" | "passing" => &mut counts.passed,
+                "failed" | "failing" => &mut counts.failed,
+                "skipped" | "ignored" | "pending" | "todo" => &mut counts.skipped,
+                "total" => &mut counts.total,
+                _ => continue,
+            };
+            slot.get_or_insert(n);
+        }
+        counts
+    }
+
+    fn is_summary(&self) -> bool {
+        self.passed.is_some() || self.failed.is_some()
+    }
+
+    /// Whether `line`, with these counts, is shaped like the start of a
+    /// summary: two or more counts, a `Tests` label (Jest, Vitest), a pytest
+    /// `===` banner or Mocha's counts
+    fn starts_summary(&self, line: &str) -> bool {
+        let line = line.trim_start();
+        let found = [self.passed, self.failed, self.skipped, self.total]
+            .iter()
+            .filter(|count| count.is_some())
+            .count();
+        self.is_summary()
+            && (found >= 2 || line.starts_with("Tests") || line.starts_with('=') || self.mocha)
+    }
+
+    /// Fill in counts this line is missing from the next line
+    fn merge(&mut self, next: Counts) -> bool {
+        let fills = |a: Option<u32>, b: Option<u32>| a.is_none() || b.is_none();
+        let compatible = fills(self.passed, next.passed)
+            && fills(self.failed, next.failed)
+            && fills(self.skipped, next.skipped)
+            && fills(self.total, next.total);
+        if compatible {
+            self.passed = self.passed.or(next.passed);
+            self.failed = self.failed.or(next.failed);
+            self.skipped = self.skipped.or(next.skipped);
+            self.total = self.total.or(next.total);
+        }
+        compatible
+    }
+}
+
+impl TestOutputParser for GenericParser {
+    fn parse(&self, output: &str) -> Result<TestSuiteResult> {
+        let mut summary: Option<Counts> = None;
+
+        for line in output.lines() {
+            let lower = line.to_lowercase();
+            // Jest's and Vitest's suite and file counts come before the tests'
+            if ["suites", "files", "snapshots"]
+                .iter()
+                .any(|w| lower.contains(w))
+            {
+                continue;
+            }
+            let counts = Counts::from_line(line);
+            let Some(current) = summary.as_mut() else {
+                if counts.starts_summary(line) {
+                    summary = Some(counts);
+                }
+                continue;
+            };
+            // Mocha prints one count per line; the summary ends at the first
+            // line that doesn't add to it
+            let merged = (counts.is_summary() || counts.skipped.is_some()) && current.merge(counts);
+            if !merged && !line.trim().is_empty() {
+                break;
+            }
+        }
+
+        let summary = summary.unwrap_or_default();
+        let passed = summary.passed.unwrap_or(0);
+        let failed = summary.failed.unwrap_or(0);
+        let skipped = summary.skipped.unwrap_or(0);
+        // Jest's total includes its skipped and todo tests
+        Ok(TestSuiteResult {
+            total: summary
+                .total
+                .map_or(passed + failed, |total| total.saturating_sub(skipped)),
+            passed,
+            failed,
+            skipped,
+            tests: vec![],
+            duration_ms: 0,
+            raw_output: output.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_summary_line() {
+        let result = GenericParser
+            .parse("running\ntest result: ok. 4 passed; 1 failed\n")
+            .unwrap();
+        assert_eq!((result.total, result.passed, result.failed), (5, 4, 1));
+
+        let result = GenericParser.parse("no summary here").unwrap();
+        assert_eq!(result.total, 0);
+    }
+
+    #[test]
+    fn test_log_lines_are_not_summaries() {
+        let output = "1 failed attempt, retrying
+connected
+==== 5 passed in 0.12s ====
+";
+        let result = GenericParser.parse(output).unwrap();
+        assert_eq!((result.total, result.passed, result.failed), (5, 5, 0));
+
+        let result = GenericParser
+            .parse(
+                "      Tests  5 passed (5)
+",
+            )
+            .unwrap();
+        assert_eq!((result.total, result.passed), (5, 5));
+    }
+
+    #[test]
+    fn test_parse_js_summaries() {
+        let jest = "Test Suites: 1 failed, 1 total\nTests:       1 failed, 4 passed, 5 total\nSnapshots:   0 total\n";
+        let result = GenericParser.parse(jest).unwrap();
+        assert_eq!((result.total, result.passed, result.failed), (5, 4, 1));
+
+        let vitest =
+            " Test Files  1 failed (1)\n      Tests  1 failed | 3 passed | 1 skipped (5)\n";
+        let result = GenericParser.parse(vitest).unwrap();
+        assert_eq!(
+            (result.total, result.passed, result.failed, result.skipped),
+            (4, 3, 1, 1)
+        );
+
+        let mocha = "  4 passing (12ms)\n  2 pending\n  1 failing\n\n  1) sum\n       overflows:\n";
+        let result = GenericParser.parse(mocha).unwrap();
+        assert_eq!(
+            (result.total, result.passed, result.failed, result.skipped),
+            (5, 4, 1, 2)
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use super::{TestCaseResult, TestOutputParser, TestSuiteResult};
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Parser for `go test -json` event streams, falling back to `go test -v`
+/// output
+pub struct GoTestParser;
+
+/// A `go test -json` (test2json) event
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TestEvent {
+    action: String,
+    #[serde(default)]
+    package: String,
+    #[serde(default)]
+    test: Option<String>,
+    #[serde(default)]
+    elapsed: Option<f64>,
+    #[serde(default)]
+    output: Option<String>,
+}
+
+/// A test (or, for events without a test, a package) and its outcome
+#[derive(Debug, Default)]
+struct TestState {
+    output: String,
+    /// `pass`, `fail` or `skip` once finished
+    outcome: Option<String>,
+    elapsed: Option<f64>,
+}
+
+impl TestOutputParser for GoTestParser {
+    fn parse(&self, output: &str) -> Result<TestSuiteResult> {
+        let events: Vec<TestEvent> = output
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+        if events.is_empty() {
+            return parse_verbose(output);
+        }
+
+        // Keyed by (package, test); the package's own events have no test
+        let mut states: BTreeMap<(String, Option<String>), TestState> = BTreeMap::new();
+        let mut order = Vec::new();
+        for event in events {
+            let key = (event.package, event.test);
+            let state = states.entry(key.clone()).or_insert_with(|| {
+                order.push(key.clone());
+                TestState::default()
+            });
+            match event.action.as_str() {
+                "output" => state.output.push_str(event.output.as_deref().unwrap_or("")),
+                "pass" | "fail" | "skip" => {
+                    state.outcome = Some(event.action);
+                    state.elapsed = event.elapsed;
+                }
+                _ => {}
+            }
+        }
+
+        let mut tests = Vec::new();
+        let mut skipped = 0;
+        for key in &order {
+            let state = &states[key];
+            let (package, test) = key;
+            let name = match test {
+                // Subtests are scored on their own, so a parent only counts
+                // when it has none, or when it failed while none of them did
+                Some(test)
+                    if any_subtest(&states, package, test, |_| true)
+                        && (!state.failed()
+                            || any_subtest(&states, package, test, TestState::failed)) =>
+                {
+                    continue
+                }
+                Some(test) => test.clone(),
+                // A package that failed without running any test (e.g. it
+                // didn't compile) counts as one failure
+                None if state.outcome.as_deref() == Some("fail")
+                    && !states.keys().any(|(p, t)| p == package && t.is_some()) =>
+                {
+                    package.clone()
+                }
+                None => continue,
+            };
+            let passed = match state.outcome.as_deref() {
+                Some("pass") => true,
+                Some("skip") => {
+                    skipped += 1;
+                    continue;
+                }
+                // Tests still running when the stream ended failed (panic or
+                // timeout)
+                _ => false,
+            };
+
+            tests.push(TestCaseResult {
+                name,
+                passed,
+                duration_ms: state.elapsed.map(|e| (e * 1000.0) as u64),
+                error: (!passed).then(|| state.output.clone()),
+                stdout: Some(state.output.clone()).filter(|o| !o.is_empty()),
+                weight: None,
+                group: None,
+            });
+        }
+
+        let mut results = super::suite_from_tests(tests, skipped, output);
+        results.duration_ms = states
+            .iter()
+            .filter(|((_, test), _)| test.is_none())
+            .filter_map(|(_, state)| state.elapsed)
+            .map(|e| (e * 1000.0) as u64)
+            .sum();
+        Ok(results)
+    }
+}
+
+impl TestState {
+    /// Whether the test failed, or was still running when the stream ended
+    fn failed(&self) -> bool {
+        !matches!(self.outcome.as_deref(), Some("pass" | "skip"))
+    }
+}
+
+/// Whether any subtest of `test` matches `predicate`
+fn any_subtest(
+    states: &BTreeMap<(String, Option<String>), TestState>,
+    package: &str,
+    test: &str,
+    predicate: impl Fn(&TestState) -> bool,
+) -> bool {
+    let prefix = format!("{}/", test);
+    states.iter().any(|((p, t), state)| {
+        p == package && t.as_deref().is_some_and(|t| t.starts_with(&prefix)) && predicate(state)
+    })
+}
+
+/// Parse `go test -v` output
+fn parse_verbose(output: &str) -> Result<TestSuiteResult> {
+    let mut tests = Vec::new();
+    let mut skipped = 0;
+
+    // Subtest results are indented under their parent's
+    for line in output.lines().map(str::trim_start) {
+        let (rest, passed) = if let Some(rest) = line.strip_prefix("--- PASS: ") {
+            (rest, true)
+        } else if let Some(rest) = line.strip_prefix("--- FAIL: ") {
+            (rest, false)
+        } else {
+            if line.starts_with("--- SKIP: ") {
+                skipped += 1;
+            }
+            continue;
+        };
+
+        tests.push(TestCaseResult {
+            name: rest
+                .split_whitespace()
+                .next()
+                .unwrap_or("unknown")
+                .to_string(),
+            passed,
+            duration_ms: None,
+            error: (!passed).then(|| "Test failed".to_string()),
+            stdout: None,
+            weight: None,
+            group: None,
+        });
+    }
+
+    Ok(super::suite_from_tests(tests, skipped, output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_go_test_output() {
+        let output = r#"
+=== RUN   TestOne
+--- PASS: TestOne (0.00s)
+=== RUN   TestTwo
+--- PASS: TestTwo (0.00s)
+=== RUN   TestThree
+--- FAIL: TestThree (0.00s)
+=== RUN   TestFour
+=== RUN   TestFour/skipped
+    --- SKIP: TestFour/skipped (0.00s)
+"#;
+
+        let result = GoTestParser.parse(output).unwrap();
+        assert_eq!(result.total, 3);
+        assert_eq!(result.passed, 2);
+        assert_eq!(result.failed, 1);
+        assert_eq!(result.skipped, 1);
+    }
+
+    #[test]
+    fn test_parse_go_test_json() {
+        let output = r#"{"Action":"start","Package":"example.com/kv"}
+{"Action":"run","Package":"example.com/kv","Test":"TestGet"}
+{"Action":"output","Package":"example.com/kv","Test":"TestGet","Output":"=== RUN   TestGet\n"}
+{"Action":"pass","Package":"example.com/kv","Test":"TestGet","Elapsed":0.01}
+{"Action":"run","Package":"example.com/kv","Test":"TestPut"}
+{"Action":"run","Package":"example.com/kv","Test":"TestPut/empty"}
+{"Action":"output","Package":"example.com/kv","Test":"TestPut/empty","Output":"    kv_test.go:20: got 1, want 0\n"}
+{"Action":"fail","Package":"example.com/kv","Test":"TestPut/empty","Elapsed":0}
+{"Action":"run","Package":"example.com/kv","Test":"TestPut/large"}
+{"Action":"skip","Package":"example.com/kv","Test":"TestPut/large","Elapsed":0}
+{"Action":"fail","Package":"example.com/kv","Test":"TestPut","Elapsed":0.02}
+{"Action":"run","Package":"example.com/kv","Test":"TestDelete"}
+{"Action":"run","Package":"example.com/kv","Test":"TestDelete/missing"}
+{"Action":"pass","Package":"example.com/kv","Test":"TestDelete/missing","Elapsed":0}
+{"Action":"output","Package":"example.com/kv","Test":"TestDelete","Output":"    kv_test.go:41: cleanup failed\n"}
+{"Action":"fail","Package":"example.com/kv","Test":"TestDelete","Elapsed":0.01}
+{"Action":"fail","Package":"example.com/kv","Elapsed":0.5}
+{"Action":"output","Package":"example.com/broken","Output":"FAIL\texample.com/broken [build failed]\n"}
+{"Action":"fail","Package":"example.com/broken","Elapsed":0}
+"#;
+
+        let result = GoTestParser.parse(output).unwrap();
+        let names: Vec<&str> = result.tests.iter().map(|t| t.name.as_str()).collect();
+        // TestDelete failed on its own, so it counts next to its subtest
+        assert_eq!(
+            names,
+            [
+                "TestGet",
+                "TestPut/empty",
+                "TestDelete",
+                "TestDelete/missing",
+                "example.com/broken"
+            ]
+        );
+        assert_eq!(
+            (result.total, result.passed, result.failed, result.skipped),
+            (5, 2, 3, 1)
+        );
+        assert_eq!(result.tests[0].duration_ms, Some(10));
+        assert!(result.tests[1]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("got 1, want 0"));
+        assert_eq!(result.duration_ms, 500);
+    }
+}
@@ -0,0 +1,156 @@
+use super::{TestCaseResult, TestOutputParser, TestSuiteResult};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// Results written by `jest --json` (vitest's `json` reporter writes the same
+/// format)
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JestResults {
+    #[serde(default)]
+    test_results: Vec<JestFileResult>,
+}
+
+/// Results of one test file
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JestFileResult {
+    name: String,
+    #[serde(default)]
+    status: Option<String>,
+    /// Why the file failed, e.g. a syntax error before any test ran
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    start_time: Option<u64>,
+    #[serde(default)]
+    end_time: Option<u64>,
+    #[serde(default)]
+    assertion_results: Vec<JestTestResult>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JestTestResult {
+    #[serde(default)]
+    full_name: Option<String>,
+    #[serde(default)]
+    ancestor_titles: Vec<String>,
+    title: String,
+    status: String,
+    #[serde(default)]
+    duration: Option<f64>,
+    #[serde(default)]
+    failure_messages: Vec<String>,
+}
+
+/// Parser for Jest and Vitest JSON results
+pub struct JestJsonParser;
+
+impl TestOutputParser for JestJsonParser {
+    fn parse(&self, output: &str) -> Result<TestSuiteResult> {
+        let results: JestResults =
+            serde_json::from_str(output).context("Invalid Jest/Vitest JSON results")?;
+        let mut tests = Vec::new();
+        let mut skipped = 0;
+        let mut duration_ms = 0;
+
+        for file in results.test_results {
+            if let (Some(start), Some(end)) = (file.start_time, file.end_time) {
+                duration_ms += end.saturating_sub(start);
+            }
+
+            // A file that failed without running tests counts as one failure
+            if file.assertion_results.is_empty() && file.status.as_deref() == Some("failed") {
+                tests.push(TestCaseResult {
+                    name: file.name,
+                    passed: false,
+                    duration_ms: None,
+                    error: Some(
+                        file.message
+                            .unwrap_or_else(|| "Test file failed".to_string()),
+                    ),
+                    stdout: None,
+                    weight: None,
+                    group: None,
+                });
+                continue;
+            }
+
+            for test in file.assertion_results {
+                let passed = match test.status.as_str() {
+                    "passed" => true,
+                    "failed" => false,
+                    // pending, skipped, todo and disabled
+                    _ => {
+                        skipped += 1;
+                        continue;
+                    }
+                };
+                let name = test.full_name.unwrap_or_else(|| {
+                    let mut titles = test.ancestor_titles;
+                    titles.push(test.title);
+                    titles.join(" ")
+                });
+
+                tests.push(TestCaseResult {
+                    name,
+                    passed,
+                    duration_ms: test.duration.map(|d| d as u64),
+                    error: (!passed).then(|| test.failure_messages.join("\n")),
+                    stdout: None,
+                    weight: None,
+                    group: None,
+                });
+            }
+        }
+
+        let mut results = super::suite_from_tests(tests, skipped, output);
+        results.duration_ms = duration_ms;
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_jest_json() {
+        let output = r#"{
+  "numTotalTests": 4,
+  "testResults": [
+    {
+      "name": "/app/sum.test.js",
+      "status": "failed",
+      "startTime": 1000,
+      "endTime": 1250,
+      "assertionResults": [
+        {"ancestorTitles": ["sum"], "fullName": "sum adds", "title": "adds", "status": "passed", "duration": 3, "failureMessages": []},
+        {"ancestorTitles": ["sum"], "title": "overflows", "status": "failed", "duration": 5, "failureMessages": ["Expected: 3\nReceived: 4"]},
+        {"ancestorTitles": [], "fullName": "later", "title": "later", "status": "todo", "failureMessages": []}
+      ]
+    },
+    {
+      "name": "/app/broken.test.js",
+      "status": "failed",
+      "message": "SyntaxError: Unexpected token",
+      "assertionResults": []
+    }
+  ]
+}"#;
+
+        let result = JestJsonParser.parse(output).unwrap();
+        let names: Vec<&str> = result.tests.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["sum adds", "sum overflows", "/app/broken.test.js"]);
+        assert_eq!(
+            (result.total, result.passed, result.failed, result.skipped),
+            (3, 1, 2, 1)
+        );
+        assert_eq!(result.duration_ms, 250);
+        assert_eq!(
+            result.tests[1].error.as_deref(),
+            Some("Expected: 3\nReceived: 4")
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use super::{TestCaseResult, TestOutputParser, TestSuiteResult};
+use anyhow::{Context, Result};
+use roxmltree::{Document, Node};
+
+/// Parser for JUnit XML reports, as written by cargo-nextest, pytest
+/// `--junitxml`, jest-junit and gotestsum
+pub struct JunitParser;
+
+impl TestOutputParser for JunitParser {
+    fn parse(&self, output: &str) -> Result<TestSuiteResult> {
+        let doc = Document::parse(output).context("Invalid JUnit XML report")?;
+        let mut tests = Vec::new();
+        let mut skipped = 0;
+
+        for case in doc.descendants().filter(|n| n.has_tag_name("testcase")) {
+            if child(case, "skipped").is_some() {
+                skipped += 1;
+                continue;
+            }
+
+            let name = case.attribute("name").unwrap_or("unknown");
+            let name = match case.attribute("classname") {
+                Some(class) if !class.is_empty() && class != name => {
+                    format!("{}::{}", class, name)
+                }
+                _ => name.to_string(),
+            };
+            let failure = child(case, "failure").or_else(|| child(case, "error"));
+
+            tests.push(TestCaseResult {
+                name,
+                passed: failure.is_none(),
+                duration_ms: seconds_to_ms(case.attribute("time")),
+                error: failure.map(failure_message),
+                stdout: child(case, "system-out")
+                    .and_then(|n| n.text())
+                    .map(String::from),
+                weight: None,
+                group: None,
+            });
+        }
+
+        let mut results = super::suite_from_tests(tests, skipped, output);
+        // pytest only times the `<testsuite>`s, not the `<testsuites>` root
+        let root = doc.root_element();
+        results.duration_ms = seconds_to_ms(root.attribute("time")).unwrap_or_else(|| {
+            root.children()
+                .filter(|n| n.has_tag_name("testsuite"))
+                .filter_map(|n| seconds_to_ms(n.attribute("time")))
+                .sum()
+        });
+        Ok(results)
+    }
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, tag: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| n.has_tag_name(tag))
+}
+
+/// The failure's details, falling back to its one-line message
+fn failure_message(failure: Node) -> String {
+    failure
+        .text()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .or_else(|| failure.attribute("message"))
+        .unwrap_or("Test failed")
+        .to_string()
+}
+
+fn seconds_to_ms(seconds: Option<&str>) -> Option<u64> {
+    let seconds: f64 = seconds?.parse().ok()?;
+    Some((seconds * 1000.0) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_junit_report() {
+        let report = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="nextest-run" tests="4" failures="1" time="1.5">
+  <testsuite name="hello" tests="4" failures="1" skipped="1">
+    <testcase name="tests::one" classname="hello" time="0.012"/>
+    <testcase name="tests::two" classname="hello" time="0.5">
+      <failure message="thread panicked" type="test failure"><![CDATA[assertion `left == right` failed]]></failure>
+      <system-out>debug output</system-out>
+    </testcase>
+    <testcase name="tests::three" classname="hello">
+      <skipped/>
+    </testcase>
+    <testcase name="tests::four" classname="hello">
+      <error message="timed out"/>
+    </testcase>
+  </testsuite>
+</testsuites>"#;
+
+        let result = JunitParser.parse(report).unwrap();
+        assert_eq!(
+            (result.total, result.passed, result.failed, result.skipped),
+            (3, 1, 2, 1)
+        );
+        assert_eq!(result.duration_ms, 1500);
+        assert_eq!(result.tests[0].name, "hello::tests::one");
+        assert_eq!(result.tests[0].duration_ms, Some(12));
+        assert_eq!(
+            result.tests[1].error.as_deref(),
+            Some("assertion `left == right` failed")
+        );
+        assert_eq!(result.tests[1].stdout.as_deref(), Some("debug output"));
+        assert_eq!(result.tests[2].error.as_deref(), Some("timed out"));
+
+        assert!(JunitParser.parse("not xml").is_err());
+    }
+}
@@ -0,0 +1,112 @@
+use super::{TestCaseResult, TestOutputParser, TestSuiteResult};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// Results written by mocha's `json` reporter
+#[derive(Debug, Deserialize)]
+struct MochaResults {
+    #[serde(default)]
+    stats: Option<MochaStats>,
+    #[serde(default)]
+    passes: Vec<MochaTest>,
+    #[serde(default)]
+    failures: Vec<MochaTest>,
+    #[serde(default)]
+    pending: Vec<MochaTest>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MochaStats {
+    #[serde(default)]
+    duration: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MochaTest {
+    full_title: String,
+    #[serde(default)]
+    duration: Option<u64>,
+    #[serde(default)]
+    err: Option<MochaError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MochaError {
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    stack: Option<String>,
+}
+
+/// Parser for mocha's `json` reporter. Failures include failing hooks, which
+/// never appear among the tests.
+pub struct MochaJsonParser;
+
+impl TestOutputParser for MochaJsonParser {
+    fn parse(&self, output: &str) -> Result<TestSuiteResult> {
+        let results: MochaResults =
+            serde_json::from_str(output).context("Invalid mocha JSON results")?;
+
+        let passes = results.passes.into_iter().map(|t| (t, true));
+        let failures = results.failures.into_iter().map(|t| (t, false));
+        let tests = passes
+            .chain(failures)
+            .map(|(test, passed)| TestCaseResult {
+                name: test.full_title,
+                passed,
+                duration_ms: test.duration,
+                error: if passed {
+                    None
+                } else {
+                    test.err
+                        .and_then(|err| err.stack.or(err.message))
+                        .or_else(|| Some("Test failed".to_string()))
+                },
+                stdout: None,
+                weight: None,
+                group: None,
+            })
+            .collect();
+
+        let mut suite = super::suite_from_tests(tests, results.pending.len() as u32, output);
+        suite.duration_ms = results.stats.and_then(|s| s.duration).unwrap_or(0);
+        Ok(suite)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mocha_json() {
+        let output = r#"{
+  "stats": {"suites": 1, "tests": 3, "passes": 1, "pending": 1, "failures": 2, "duration": 42},
+  "tests": [],
+  "passes": [{"title": "adds", "fullTitle": "sum adds", "duration": 2, "err": {}}],
+  "failures": [
+    {"title": "overflows", "fullTitle": "sum overflows", "duration": 1,
+     "err": {"message": "expected 4 to equal 3", "stack": "AssertionError: expected 4 to equal 3\n    at Context.<anonymous>"}},
+    {"title": "\"before all\" hook", "fullTitle": "db \"before all\" hook", "err": {"message": "connect ECONNREFUSED"}}
+  ],
+  "pending": [{"title": "later", "fullTitle": "sum later", "err": {}}]
+}"#;
+
+        let result = MochaJsonParser.parse(output).unwrap();
+        assert_eq!(
+            (result.total, result.passed, result.failed, result.skipped),
+            (3, 1, 2, 1)
+        );
+        assert_eq!(result.duration_ms, 42);
+        assert!(result.tests[1]
+            .error
+            .as_deref()
+            .unwrap()
+            .starts_with("AssertionError"));
+        assert_eq!(
+            result.tests[2].error.as_deref(),
+            Some("connect ECONNREFUSED")
+        );
+    }
+}
@@ -0,0 +1,186 @@
+//! Test output parsers
+//!
+//! Each test runner reports results in its own format. A [`TestOutputParser`]
+//! turns one of those formats into a [`TestSuiteResult`]; both runners pick
+//! the parser for a harness with [`parse_test_results`], so pods and local
+//! runs score the same output the same way.
+
+mod cargo;
+mod generic;
+mod go;
+mod jest;
+mod junit;
+mod mocha;
+mod pytest;
+mod results_file;
+mod tap;
+
+pub use cargo::CargoTestParser;
+pub use generic::GenericParser;
+pub use go::GoTestParser;
+pub use jest::JestJsonParser;
+pub use junit::JunitParser;
+pub use mocha::MochaJsonParser;
+pub use pytest::{PytestJsonParser, PytestParser};
+pub use results_file::{ResultsFileParser, RESULTS_FILE_ENV};
+pub use tap::TapParser;
+
+use super::{TestCaseResult, TestSuiteResult};
+use crate::cli::{NpmReporter, ReportFormat, TestHarness};
+use anyhow::Result;
+
+/// Turns a test runner's output into test results
+pub trait TestOutputParser {
+    /// Parse the runner's output (its console output, or the contents of the
+    /// report file it wrote)
+    fn parse(&self, output: &str) -> Result<TestSuiteResult>;
+}
+
+/// Parser for a harness's console output
+pub fn console_parser(harness: &TestHarness) -> Box<dyn TestOutputParser> {
+    match harness {
+        TestHarness::Cargo { .. } => Box::new(CargoTestParser),
+        TestHarness::Npm {
+            reporter: Some(NpmReporter::Tap),
+            ..
+        } => Box::new(TapParser),
+        TestHarness::Pytest { .. } => Box::new(PytestParser),
+        TestHarness::Go { .. } => Box::new(GoTestParser),
+        TestHarness::Npm { .. } | TestHarness::Custom { .. } | TestHarness::Shell { .. } => {
+            Box::new(GenericParser)
+        }
+    }
+}
+
+/// Parser for a report file format
+pub fn report_parser(format: ReportFormat) -> Box<dyn TestOutputParser> {
+    match format {
+        ReportFormat::Junit => Box::new(JunitParser),
+        ReportFormat::PytestJson => Box::new(PytestJsonParser),
+        ReportFormat::JestJson => Box::new(JestJsonParser),
+        ReportFormat::MochaJson => Box::new(MochaJsonParser),
+        ReportFormat::Tap => Box::new(TapParser),
+    }
+}
+
+/// Everything a harness run produced that results can be read from
+#[derive(Debug, Clone, Default)]
+pub struct HarnessOutput {
+    /// Combined stdout and stderr of the test command
+    pub console: String,
+    /// Contents of `$ANODE_RESULTS_FILE`, if the harness wrote it
+    pub results_file: Option<String>,
+    /// Contents of the harness's configured report file, if it was written
+    pub report: Option<String>,
+    /// Wall time of the test command, if it was measured
+    pub duration_ms: Option<u64>,
+}
+
+/// Parse the results of a harness run, from the first of these that exists:
+/// the `$ANODE_RESULTS_FILE` the harness wrote, its report file, or its
+/// console output. The console output is always kept as the raw
+/// output.
+pub fn parse_test_results(
+    harness: &TestHarness,
+    output: &HarnessOutput,
+) -> Result<TestSuiteResult> {
+    let mut results = match (&output.results_file, harness.report(), &output.report) {
+        (Some(results_file), _, _) => ResultsFileParser.parse(results_file)?,
+        (None, Some(config), Some(report)) => report_parser(config.format).parse(report)?,
+        (None, Some(config), None) => {
+            tracing::warn!(
+                "Test report {:?} was not written, parsing console output",
+                config.path
+            );
+            console_parser(harness).parse(&output.console)?
+        }
+        (None, None, _) => console_parser(harness).parse(&output.console)?,
+    };
+    results.raw_output = output.console.clone();
+    if let Some(duration_ms) = output.duration_ms {
+        results.duration_ms = duration_ms;
+    }
+    Ok(results)
+}
+
+/// Build a suite result from the tests that ran, counting passes and
+/// failures, and the number of skipped tests
+fn suite_from_tests(tests: Vec<TestCaseResult>, skipped: u32, raw_output: &str) -> TestSuiteResult {
+    let passed = tests.iter().filter(|t| t.passed).count() as u32;
+    let failed = tests.len() as u32 - passed;
+    TestSuiteResult {
+        total: passed + failed,
+        passed,
+        failed,
+        skipped,
+        tests,
+        duration_ms: 0,
+        raw_output: raw_output.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::TestReport;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_result_sources_in_order_of_preference() {
+        let harness = TestHarness::Custom {
+            command: "cargo".to_string(),
+            args: vec!["nextest".to_string(), "run".to_string()],
+            timeout_seconds: None,
+            report: Some(TestReport {
+                format: ReportFormat::Junit,
+                path: PathBuf::from("target/nextest/default/junit.xml"),
+            }),
+        };
+        let mut output = HarnessOutput {
+            console: "1 passed, 1 failed".to_string(),
+            results_file: None,
+            report: None,
+            duration_ms: None,
+        };
+
+        // Without the report the console output is all there is
+        let results = parse_test_results(&harness, &output).unwrap();
+        assert_eq!((results.total, results.failed), (2, 1));
+
+        output.report =
+            Some(r#"<testsuite><testcase name="a"/><testcase name="b"/></testsuite>"#.to_string());
+        let results = parse_test_results(&harness, &output).unwrap();
+        assert_eq!((results.total, results.passed), (2, 2));
+        assert_eq!(results.raw_output, output.console);
+
+        // The measured wall time replaces the parser's
+        output.duration_ms = Some(1520);
+        let results = parse_test_results(&harness, &output).unwrap();
+        assert_eq!(results.duration_ms, 1520);
+
+        // The results file takes precedence over both
+        output.results_file = Some(r#"{"tests": [{"name": "c", "status": "failed"}]}"#.to_string());
+        let results = parse_test_results(&harness, &output).unwrap();
+        assert_eq!((results.total, results.failed), (1, 1));
+    }
+
+    #[test]
+    fn test_skipped_tests_are_not_scored() {
+        let junit = r#"<testsuite>
+  <testcase name="a"/>
+  <testcase name="b"><skipped/></testcase>
+</testsuite>"#;
+        let results_file = r#"{"tests": [
+  {"name": "a", "status": "passed"},
+  {"name": "b", "status": "skipped", "weight": 5}
+]}"#;
+        for results in [
+            JunitParser.parse(junit).unwrap(),
+            ResultsFileParser.parse(results_file).unwrap(),
+            GenericParser.parse("Tests: 1 todo, 1 passed, 2 total").unwrap(),
+        ] {
+            assert_eq!((results.total, results.skipped), (1, 1));
+            assert_eq!(results.pass_rate(), 100.0);
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use super::{TestCaseResult, TestOutputParser, TestSuiteResult};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// Parser for `pytest -v` console output, used when no report was written
+pub struct PytestParser;
+
+impl TestOutputParser for PytestParser {
+    fn parse(&self, output: &str) -> Result<TestSuiteResult> {
+        let mut tests = Vec::new();
+        let mut skipped = 0;
+
+        // Only `<nodeid> <OUTCOME>` lines are results; log output and the
+        // short test summary (`FAILED <nodeid> - ...`) are not
+        for line in output.lines() {
+            let mut words = line.split_whitespace();
+            let (Some(node_id), Some(outcome)) = (words.next(), words.next()) else {
+                continue;
+            };
+            if !node_id.contains("::") {
+                continue;
+            }
+            // Expected failures count as skipped, as in pytest's JUnit report
+            let passed = match outcome {
+                "PASSED" | "XPASS" => true,
+                "FAILED" | "ERROR" => false,
+                "SKIPPED" | "XFAIL" => {
+                    skipped += 1;
+                    continue;
+                }
+                _ => continue,
+            };
+
+            tests.push(TestCaseResult {
+                name: node_id.to_string(),
+                passed,
+                duration_ms: None,
+                error: (!passed).then(|| "Test failed".to_string()),
+                stdout: None,
+                weight: None,
+                group: None,
+            });
+        }
+
+        Ok(super::suite_from_tests(tests, skipped, output))
+    }
+}
+
+/// Report written by the pytest-json-report plugin
+#[derive(Debug, Deserialize)]
+struct JsonReport {
+    #[serde(default)]
+    duration: Option<f64>,
+    #[serde(default)]
+    tests: Vec<JsonReportTest>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonReportTest {
+    nodeid: String,
+    outcome: String,
+    #[serde(default)]
+    setup: Option<JsonReportStage>,
+    #[serde(default)]
+    call: Option<JsonReportStage>,
+    #[serde(default)]
+    teardown: Option<JsonReportStage>,
+}
+
+/// The setup, call or teardown stage of a test
+#[derive(Debug, Deserialize)]
+struct JsonReportStage {
+    #[serde(default)]
+    duration: Option<f64>,
+    #[serde(default)]
+    longrepr: Option<String>,
+    #[serde(default)]
+    stdout: Option<String>,
+}
+
+/// Parser for pytest-json-report's `--json-report-file`
+pub struct PytestJsonParser;
+
+impl TestOutputParser for PytestJsonParser {
+    fn parse(&self, output: &str) -> Result<TestSuiteResult> {
+        let report: JsonReport =
+            serde_json::from_str(output).context("Invalid pytest JSON report")?;
+        let mut tests = Vec::new();
+        let mut skipped = 0;
+
+        for test in report.tests {
+            let passed = match test.outcome.as_str() {
+                "passed" | "xpassed" => true,
+                "skipped" | "xfailed" => {
+                    skipped += 1;
+                    continue;
+                }
+                _ => false,
+            };
+            let stages = [&test.setup, &test.call, &test.teardown];
+            let stages = stages.iter().filter_map(|stage| stage.as_ref());
+            let duration: f64 = stages.clone().filter_map(|s| s.duration).sum();
+
+            tests.push(TestCaseResult {
+                name: test.nodeid,
+                passed,
+                duration_ms: Some((duration * 1000.0) as u64),
+                error: if passed {
+                    None
+                } else {
+                    stages
+                        .clone()
+                        .find_map(|s| s.longrepr.clone())
+                        .or_else(|| Some("Test failed".to_string()))
+                },
+                stdout: test.call.and_then(|call| call.stdout),
+                weight: None,
+                group: None,
+            });
+        }
+
+        let mut results = super::suite_from_tests(tests, skipped, output);
+        results.duration_ms = report.duration.map_or(0, |d| (d * 1000.0) as u64);
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pytest_output() {
+        let output = r#"
+test_example.py::test_one PASSED
+test_example.py::test_two PASSED
+test_example.py::test_three FAILED
+test_example.py::test_four SKIPPED (not ready)
+test_example.py::test_five XFAIL (known bug)
+test_example.py::test_six XPASS
+INFO root: PASSED the smoke check
+=========================== short test summary info ============================
+FAILED test_example.py::test_three - assert 1 == 2
+"#;
+
+        let result = PytestParser.parse(output).unwrap();
+        assert_eq!(result.total, 4);
+        assert_eq!(result.passed, 3);
+        assert_eq!(result.failed, 1);
+        assert_eq!(result.skipped, 2);
+        assert_eq!(result.tests[2].name, "test_example.py::test_three");
+    }
+
+    #[test]
+    fn test_parse_pytest_json_report() {
+        let report = r#"{
+  "duration": 0.5,
+  "tests": [
+    {"nodeid": "test_example.py::test_one", "outcome": "passed",
+     "setup": {"duration": 0.001, "outcome": "passed"},
+     "call": {"duration": 0.02, "outcome": "passed", "stdout": "hello\n"},
+     "teardown": {"duration": 0.001, "outcome": "passed"}},
+    {"nodeid": "test_example.py::test_two", "outcome": "failed",
+     "setup": {"duration": 0.001, "outcome": "passed"},
+     "call": {"duration": 0.1, "outcome": "failed", "longrepr": "assert 1 == 2"}},
+    {"nodeid": "test_example.py::test_three", "outcome": "skipped",
+     "setup": {"duration": 0.0, "outcome": "skipped", "longrepr": "not ready"}},
+    {"nodeid": "test_example.py::test_four", "outcome": "xfailed",
+     "call": {"duration": 0.01, "outcome": "skipped", "longrepr": "known bug"}}
+  ]
+}"#;
+
+        let result = PytestJsonParser.parse(report).unwrap();
+        assert_eq!(
+            (result.total, result.passed, result.failed, result.skipped),
+            (2, 1, 1, 2)
+        );
+        assert_eq!(result.duration_ms, 500);
+        assert_eq!(result.tests[0].duration_ms, Some(22));
+        assert_eq!(result.tests[0].stdout.as_deref(), Some("hello\n"));
+        assert_eq!(result.tests[1].error.as_deref(), Some("assert 1 == 2"));
+    }
+}
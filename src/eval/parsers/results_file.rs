@@ -0,0 +1,113 @@
+use super::{TestCaseResult, TestOutputParser, TestSuiteResult};
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+/// Environment variable naming the file a harness may write its results to
+pub const RESULTS_FILE_ENV: &str = "ANODE_RESULTS_FILE";
+
+/// Contents of an `$ANODE_RESULTS_FILE`
+#[derive(Debug, Deserialize)]
+struct ResultsFile {
+    tests: Vec<ResultsFileTest>,
+}
+
+/// One test in an `$ANODE_RESULTS_FILE`
+#[derive(Debug, Deserialize)]
+struct ResultsFileTest {
+    name: String,
+    status: TestStatus,
+    #[serde(default)]
+    duration_ms: Option<u64>,
+    /// Failure message
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    weight: Option<f64>,
+    #[serde(default)]
+    group: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum TestStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+/// Parser for the JSON results file a harness writes to `$ANODE_RESULTS_FILE`
+pub struct ResultsFileParser;
+
+impl TestOutputParser for ResultsFileParser {
+    fn parse(&self, output: &str) -> Result<TestSuiteResult> {
+        let file: ResultsFile = serde_json::from_str(output)
+            .with_context(|| format!("Invalid {}", RESULTS_FILE_ENV))?;
+        let mut tests = Vec::new();
+        let mut skipped = 0;
+
+        for test in file.tests {
+            if let Some(weight) = test.weight.filter(|w| *w < 0.0) {
+                bail!("Test {} has invalid weight {}", test.name, weight);
+            }
+            // Skipped tests aren't scored, so their weight doesn't matter
+            let passed = match test.status {
+                TestStatus::Skipped => {
+                    skipped += 1;
+                    continue;
+                }
+                status => status == TestStatus::Passed,
+            };
+
+            tests.push(TestCaseResult {
+                name: test.name,
+                passed,
+                duration_ms: test.duration_ms,
+                error: test
+                    .message
+                    .or_else(|| (!passed).then(|| "Test failed".to_string())),
+                stdout: None,
+                weight: test.weight,
+                group: test.group,
+            });
+        }
+
+        Ok(super::suite_from_tests(tests, skipped, output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_results_file() {
+        let file = r#"{
+  "tests": [
+    {"name": "people::create", "status": "passed", "duration_ms": 12, "group": "people", "weight": 3},
+    {"name": "people::delete", "status": "failed", "message": "expected 204, got 500", "group": "people"},
+    {"name": "search", "status": "skipped"}
+  ]
+}"#;
+
+        let result = ResultsFileParser.parse(file).unwrap();
+        assert_eq!(
+            (result.total, result.passed, result.failed, result.skipped),
+            (2, 1, 1, 1)
+        );
+        assert_eq!(result.tests[0].duration_ms, Some(12));
+        assert_eq!(
+            result.tests[1].error.as_deref(),
+            Some("expected 204, got 500")
+        );
+        assert_eq!(result.group_counts()["people"], (1, 2));
+        // Weighted: 3 of 4, the skipped test not counting
+        assert_eq!(result.pass_rate(), 75.0);
+
+        assert!(ResultsFileParser
+            .parse(r#"{"tests": [{"name": "a", "status": "ok"}]}"#)
+            .is_err());
+        assert!(ResultsFileParser
+            .parse(r#"{"tests": [{"name": "a", "status": "passed", "weight": -1}]}"#)
+            .is_err());
+    }
+}
@@ -0,0 +1,153 @@
+use super::{TestCaseResult, TestOutputParser, TestSuiteResult};
+use anyhow::Result;
+
+/// Parser for TAP (Test Anything Protocol) output, as printed by
+/// `node --test`, `tap` and mocha's `tap` reporter. Lines that aren't TAP,
+/// like npm's script banner, are ignored.
+pub struct TapParser;
+
+/// A parsed `ok`/`not ok` line
+struct TestPoint<'a> {
+    ok: bool,
+    description: &'a str,
+    /// `# SKIP` or `# TODO`: the result doesn't count
+    skipped: bool,
+}
+
+impl TestOutputParser for TapParser {
+    fn parse(&self, output: &str) -> Result<TestSuiteResult> {
+        let mut tests: Vec<TestCaseResult> = Vec::new();
+        let mut skipped = 0;
+        let mut previous_indent = None;
+        // Whether the last line was a failing test, whose YAML diagnostics
+        // block may follow
+        let mut after_failure = false;
+        let mut diagnostics: Option<Vec<&str>> = None;
+
+        for line in output.lines() {
+            let trimmed = line.trim_start();
+            let indent = line.len() - trimmed.len();
+
+            if let Some(lines) = diagnostics.as_mut() {
+                if trimmed == "..." {
+                    if let Some(test) = tests.last_mut() {
+                        test.error = Some(lines.join("\n"));
+                    }
+                    diagnostics = None;
+                } else {
+                    lines.push(trimmed);
+                }
+                continue;
+            }
+            if trimmed == "---" && after_failure {
+                diagnostics = Some(Vec::new());
+                continue;
+            }
+
+            let Some(point) = test_point(trimmed) else {
+                continue;
+            };
+            // Subtests are printed before their parent's result, so a result
+            // following deeper ones belongs to a parent and is not counted
+            let is_parent = previous_indent.is_some_and(|previous| previous > indent);
+            previous_indent = Some(indent);
+            after_failure = false;
+            if is_parent {
+                continue;
+            }
+            if point.skipped {
+                skipped += 1;
+                continue;
+            }
+
+            after_failure = !point.ok;
+            tests.push(TestCaseResult {
+                name: point.description.to_string(),
+                passed: point.ok,
+                duration_ms: None,
+                error: (!point.ok).then(|| "Test failed".to_string()),
+                stdout: None,
+                weight: None,
+                group: None,
+            });
+        }
+
+        Ok(super::suite_from_tests(tests, skipped, output))
+    }
+}
+
+fn test_point(line: &str) -> Option<TestPoint<'_>> {
+    let (ok, rest) = match line.strip_prefix("not ok") {
+        Some(rest) => (false, rest),
+        None => (true, line.strip_prefix("ok")?),
+    };
+    if !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+
+    // `ok 3 - description # SKIP reason`
+    let rest = rest
+        .trim_start()
+        .trim_start_matches(|c: char| c.is_ascii_digit());
+    let rest = rest.trim_start();
+    let rest = rest.strip_prefix("- ").unwrap_or(rest);
+    let (description, directive) = match rest.split_once(" # ") {
+        Some((description, directive)) => (description, directive),
+        None => match rest.strip_prefix("# ") {
+            Some(directive) => ("", directive),
+            None => (rest, ""),
+        },
+    };
+    let directive = directive.trim_start().to_ascii_uppercase();
+
+    Some(TestPoint {
+        ok,
+        description: description.trim(),
+        skipped: directive.starts_with("SKIP") || directive.starts_with("TODO"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tap() {
+        let output = r#"
+> app@1.0.0 test
+> node --test
+
+TAP version 13
+# Subtest: sum
+    # Subtest: adds
+    ok 1 - adds
+      ---
+      duration_ms: 0.5
+      ...
+    # Subtest: overflows
+    not ok 2 - overflows
+      ---
+      duration_ms: 0.7
+      error: 'Expected values to be strictly equal'
+      ...
+    1..2
+not ok 1 - sum
+ok 2 - standalone
+ok 3 - later # TODO not written yet
+ok 4 # skip needs network
+1..4
+"#;
+
+        let result = TapParser.parse(output).unwrap();
+        let names: Vec<&str> = result.tests.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["adds", "overflows", "standalone"]);
+        assert_eq!(
+            (result.total, result.passed, result.failed, result.skipped),
+            (3, 2, 1, 2)
+        );
+        assert_eq!(
+            result.tests[1].error.as_deref(),
+            Some("duration_ms: 0.7\nerror: 'Expected values to be strictly equal'")
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::cli::RunFilter;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
@@ -15,12 +16,18 @@ pub struct TestCaseResult {
     pub error: Option<String>,
     /// stdout output
     pub stdout: Option<String>,
+    /// Weight of the test in the score (1 when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,
+    /// Group the test belongs to, for a per-group breakdown
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
 }
 
 /// Result of running the eval test suite
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TestSuiteResult {
-    /// Total number of tests
+    /// Number of tests that ran (skipped tests are only counted in `skipped`)
     pub total: u32,
     /// Number of passed tests
     pub passed: u32,
@@ -37,13 +44,47 @@ pub struct TestSuiteResult {
 }
 
 impl TestSuiteResult {
-    /// Calculate the pass rate as a percentage
+    /// Calculate the pass rate as a percentage: the passed share of the total
+    /// weight of the tests that ran. Tests count with their weight (1 when
+    /// unset), tests only counted in the totals count once and skipped tests
+    /// don't count.
     pub fn pass_rate(&self) -> f64 {
-        if self.total == 0 {
-            0.0
+        let weight = |t: &TestCaseResult| t.weight.unwrap_or(1.0);
+        let listed_passed = self.tests.iter().filter(|t| t.passed).count() as u32;
+        let unlisted = self.total.saturating_sub(self.tests.len() as u32);
+        let unlisted_passed = self.passed.saturating_sub(listed_passed);
+
+        let total = self.tests.iter().map(weight).sum::<f64>() + unlisted as f64;
+        let passed = self
+            .tests
+            .iter()
+            .filter(|t| t.passed)
+            .map(weight)
+            .sum::<f64>()
+            + unlisted_passed as f64;
+        if total > 0.0 {
+            passed / total * 100.0
         } else {
-            (self.passed as f64 / self.total as f64) * 100.0
+            0.0
+        }
+    }
+
+    /// Whether at least one test ran and none failed
+    pub fn all_passed(&self) -> bool {
+        self.total > 0 && self.failed == 0
+    }
+
+    /// Passed and total test counts of each test group
+    pub fn group_counts(&self) -> BTreeMap<&str, (u32, u32)> {
+        let mut groups = BTreeMap::new();
+        for test in &self.tests {
+            if let Some(group) = &test.group {
+                let (passed, total) = groups.entry(group.as_str()).or_insert((0, 0));
+                *passed += test.passed as u32;
+                *total += 1;
+            }
         }
+        groups
     }
 }
 
@@ -60,6 +101,9 @@ pub struct EvalRunResult {
     pub agent_tool: String,
     /// Model version
     pub model: String,
+    /// Which of the combination's samples this run is (1-based)
+    #[serde(default = "default_sample")]
+    pub sample: u32,
     /// Start time
     pub started_at: DateTime<Utc>,
     /// End time
@@ -76,16 +120,20 @@ pub struct EvalRunResult {
     pub agent_logs: Option<String>,
     /// Error message if failed
     pub error: Option<String>,
+    /// Output of the harness's build step, if it failed
+    pub build_output: Option<String>,
     /// Maximum iterations (turns) configured for this run
     pub max_iterations: Option<u32>,
     /// Actual number of turns/iterations used by the agent
     pub turns_used: Option<u32>,
+    /// Number of agent invocations (ralph-loop iterations) used
+    pub iterations_used: Option<u32>,
     /// Token usage statistics
     pub token_usage: Option<TokenUsage>,
 }
 
 /// Token usage statistics from an agent run
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TokenUsage {
     /// Total input tokens used
     pub input_tokens: u64,
@@ -99,6 +147,17 @@ pub struct TokenUsage {
     pub total_cost_usd: f64,
 }
 
+impl TokenUsage {
+    /// Add another usage record to this one
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_read_input_tokens += other.cache_read_input_tokens;
+        self.cache_creation_input_tokens += other.cache_creation_input_tokens;
+        self.total_cost_usd += other.total_cost_usd;
+    }
+}
+
 impl EvalRunResult {
     pub fn new(run_id: &str, prompt_id: &str, agent_id: &str, agent_tool: &str, model: &str) -> Self {
         Self {
@@ -107,6 +166,7 @@ impl EvalRunResult {
             agent_id: agent_id.to_string(),
             agent_tool: agent_tool.to_string(),
             model: model.to_string(),
+            sample: default_sample(),
             started_at: Utc::now(),
             completed_at: None,
             duration_seconds: None,
@@ -115,12 +175,19 @@ impl EvalRunResult {
             score: None,
             agent_logs: None,
             error: None,
+            build_output: None,
             max_iterations: None,
             turns_used: None,
+            iterations_used: None,
             token_usage: None,
         }
     }
 
+    pub fn with_sample(mut self, sample: u32) -> Self {
+        self.sample = sample;
+        self
+    }
+
     pub fn with_max_iterations(mut self, max_iterations: u32) -> Self {
         self.max_iterations = Some(max_iterations);
         self
@@ -130,6 +197,10 @@ impl EvalRunResult {
         self.turns_used = Some(turns);
     }
 
+    pub fn set_iterations_used(&mut self, iterations: u32) {
+        self.iterations_used = Some(iterations);
+    }
+
     pub fn set_token_usage(&mut self, usage: TokenUsage) {
         self.token_usage = Some(usage);
     }
@@ -157,6 +228,25 @@ impl EvalRunResult {
         self.status = RunStatus::Failed;
         self.score = Some(0.0);
     }
+
+    /// Record that the harness's build step failed, so no tests ran
+    pub fn fail_build(&mut self, build_output: &str) {
+        self.fail_with_error("Build failed");
+        self.build_output = Some(build_output.to_string());
+        self.status = RunStatus::BuildFailed;
+    }
+
+    /// Whether the run has finished and its score counts towards the results
+    fn is_scored(&self) -> bool {
+        matches!(
+            self.status,
+            RunStatus::Completed | RunStatus::Failed | RunStatus::BuildFailed | RunStatus::Timeout
+        )
+    }
+}
+
+fn default_sample() -> u32 {
+    1
 }
 
 /// Status of a run
@@ -167,6 +257,8 @@ pub enum RunStatus {
     Running,
     Completed,
     Failed,
+    /// The harness's build step failed after the agent ran
+    BuildFailed,
     Timeout,
     Cancelled,
 }
@@ -190,14 +282,69 @@ pub struct AgentScore {
     pub total_tests: u32,
     /// Total passed tests
     pub passed_tests: u32,
-    /// Average score (pass rate)
+    /// Average score of the agent's finished runs, in which failed runs
+    /// score 0
     pub average_score: f64,
     /// Rank among all agents
     pub rank: u32,
+    /// Score statistics for each prompt, across the samples run
+    #[serde(default)]
+    pub prompt_scores: BTreeMap<String, ScoreStats>,
     /// Individual run results
     pub runs: Vec<String>, // run_ids
 }
 
+/// Statistics over the scores of repeated runs of a (prompt, agent)
+/// combination
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScoreStats {
+    /// Number of finished runs
+    pub samples: u32,
+    /// Mean score
+    pub mean: f64,
+    /// Sample standard deviation (0 with fewer than two runs)
+    pub std_dev: f64,
+    /// Lowest score
+    pub min: f64,
+    /// Highest score
+    pub max: f64,
+}
+
+impl ScoreStats {
+    pub fn from_scores(scores: &[f64]) -> Self {
+        if scores.is_empty() {
+            return Self::default();
+        }
+        let n = scores.len() as f64;
+        let mean = scores.iter().sum::<f64>() / n;
+        let std_dev = if scores.len() < 2 {
+            0.0
+        } else {
+            let variance = scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1.0);
+            variance.sqrt()
+        };
+        Self {
+            samples: scores.len() as u32,
+            mean,
+            std_dev,
+            min: scores.iter().copied().fold(f64::INFINITY, f64::min),
+            max: scores.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+}
+
+/// Score statistics for one (prompt, agent) combination
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CombinationScore {
+    /// Prompt ID
+    pub prompt_id: String,
+    /// Agent configuration identifier
+    pub agent_id: String,
+    /// Statistics over the combination's runs
+    #[serde(flatten)]
+    pub stats: ScoreStats,
+}
+
 /// Complete evaluation results
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EvaluationResults {
@@ -209,10 +356,19 @@ pub struct EvaluationResults {
     pub started_at: DateTime<Utc>,
     /// End time
     pub completed_at: Option<DateTime<Utc>>,
+    /// Prompts and agents selected on the command line, for partial runs
+    #[serde(default, skip_serializing_if = "RunFilter::is_empty")]
+    pub filter: RunFilter,
+    /// Settings overridden on the command line (`--set`), by key
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub overrides: BTreeMap<String, String>,
     /// Individual run results
     pub runs: Vec<EvalRunResult>,
     /// Agent scores and rankings
     pub agent_scores: Vec<AgentScore>,
+    /// Score statistics for each (prompt, agent) combination
+    #[serde(default)]
+    pub combination_scores: Vec<CombinationScore>,
     /// Summary statistics
     pub summary: EvalSummary,
 }
@@ -228,11 +384,14 @@ pub struct EvalSummary {
     pub failed: u32,
     /// Timed out combinations
     pub timed_out: u32,
+    /// Combinations whose build step failed
+    #[serde(default)]
+    pub build_failed: u32,
     /// Total tests run
     pub total_tests: u32,
     /// Total passed tests
     pub passed_tests: u32,
-    /// Overall pass rate
+    /// Average score of all finished runs
     pub overall_pass_rate: f64,
     /// Best performing agent
     pub best_agent: Option<String>,
@@ -247,13 +406,17 @@ impl EvaluationResults {
             eval_id: eval_id.to_string(),
             started_at: Utc::now(),
             completed_at: None,
+            filter: RunFilter::default(),
+            overrides: BTreeMap::new(),
             runs: Vec::new(),
             agent_scores: Vec::new(),
+            combination_scores: Vec::new(),
             summary: EvalSummary {
                 total_combinations: 0,
                 completed: 0,
                 failed: 0,
                 timed_out: 0,
+                build_failed: 0,
                 total_tests: 0,
                 passed_tests: 0,
                 overall_pass_rate: 0.0,
@@ -263,6 +426,18 @@ impl EvaluationResults {
         }
     }
 
+    /// Record the filter a partial run was started with
+    pub fn with_filter(mut self, filter: RunFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Record the settings overridden for this run
+    pub fn with_overrides(mut self, overrides: BTreeMap<String, String>) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
     /// Add a run result
     pub fn add_run(&mut self, result: EvalRunResult) {
         self.runs.push(result);
@@ -284,6 +459,7 @@ impl EvaluationResults {
                 passed_tests: 0,
                 average_score: 0.0,
                 rank: 0,
+                prompt_scores: BTreeMap::new(),
                 runs: Vec::new(),
             });
 
@@ -298,17 +474,45 @@ impl EvaluationResults {
                         entry.passed_tests += test_results.passed;
                     }
                 }
-                RunStatus::Failed | RunStatus::Timeout => {
+                RunStatus::Failed | RunStatus::BuildFailed | RunStatus::Timeout => {
                     entry.failed_runs += 1;
                 }
                 _ => {}
             }
         }
 
+        // Aggregate the (weighted) scores of each combination's samples and of
+        // each agent's runs
+        let mut samples: BTreeMap<(String, String), Vec<f64>> = BTreeMap::new();
+        let mut run_scores: BTreeMap<&str, Vec<f64>> = BTreeMap::new();
+        for run in self.runs.iter().filter(|r| r.is_scored()) {
+            let score = run.score.unwrap_or(0.0);
+            samples
+                .entry((run.prompt_id.clone(), run.agent_id.clone()))
+                .or_default()
+                .push(score);
+            run_scores.entry(&run.agent_id).or_default().push(score);
+        }
+        self.combination_scores = samples
+            .into_iter()
+            .map(|((prompt_id, agent_id), scores)| CombinationScore {
+                prompt_id,
+                agent_id,
+                stats: ScoreStats::from_scores(&scores),
+            })
+            .collect();
+        for combination in &self.combination_scores {
+            if let Some(score) = agent_map.get_mut(&combination.agent_id) {
+                score
+                    .prompt_scores
+                    .insert(combination.prompt_id.clone(), combination.stats.clone());
+            }
+        }
+
         // Calculate average scores
         for score in agent_map.values_mut() {
-            if score.total_tests > 0 {
-                score.average_score = (score.passed_tests as f64 / score.total_tests as f64) * 100.0;
+            if let Some(scores) = run_scores.get(score.agent_id.as_str()) {
+                score.average_score = ScoreStats::from_scores(scores).mean;
             }
         }
 
@@ -337,13 +541,16 @@ impl EvaluationResults {
             .iter()
             .filter(|r| r.status == RunStatus::Timeout)
             .count() as u32;
+        self.summary.build_failed = self
+            .runs
+            .iter()
+            .filter(|r| r.status == RunStatus::BuildFailed)
+            .count() as u32;
         self.summary.total_tests = scores.iter().map(|s| s.total_tests).sum();
         self.summary.passed_tests = scores.iter().map(|s| s.passed_tests).sum();
 
-        if self.summary.total_tests > 0 {
-            self.summary.overall_pass_rate =
-                (self.summary.passed_tests as f64 / self.summary.total_tests as f64) * 100.0;
-        }
+        let all_scores: Vec<f64> = run_scores.into_values().flatten().collect();
+        self.summary.overall_pass_rate = ScoreStats::from_scores(&all_scores).mean;
 
         self.summary.best_agent = scores.first().map(|s| s.agent_id.clone());
         self.summary.worst_agent = scores.last().map(|s| s.agent_id.clone());
@@ -374,7 +581,13 @@ impl EvaluationResults {
         if let Some(completed) = self.completed_at {
             report.push_str(&format!("Completed: {}\n", completed));
         }
-        report.push_str("\n");
+        if !self.filter.is_empty() {
+            report.push_str(&format!("Filter: {}\n", self.filter));
+        }
+        for (key, value) in &self.overrides {
+            report.push_str(&format!("Override: {}={}\n", key, value));
+        }
+        report.push('\n');
 
         report.push_str("## Summary\n\n");
         report.push_str(&format!(
@@ -384,13 +597,14 @@ impl EvaluationResults {
         report.push_str(&format!("- Completed: {}\n", self.summary.completed));
         report.push_str(&format!("- Failed: {}\n", self.summary.failed));
         report.push_str(&format!("- Timed Out: {}\n", self.summary.timed_out));
+        report.push_str(&format!("- Build Failed: {}\n", self.summary.build_failed));
         report.push_str(&format!("- Total Tests: {}\n", self.summary.total_tests));
         report.push_str(&format!("- Passed Tests: {}\n", self.summary.passed_tests));
         report.push_str(&format!(
             "- Overall Pass Rate: {:.2}%\n",
             self.summary.overall_pass_rate
         ));
-        report.push_str("\n");
+        report.push('\n');
 
         report.push_str("## Agent Rankings\n\n");
         report.push_str("| Rank | Agent | Model | Score | Tests Passed | Runs |\n");
@@ -410,10 +624,38 @@ impl EvaluationResults {
             ));
         }
 
+        if !self.combination_scores.is_empty() {
+            report.push_str("\n## Scores by Prompt\n\n");
+            report.push_str("| Prompt | Agent | Samples | Mean | Std Dev | Min | Max |\n");
+            report.push_str("|--------|-------|---------|------|---------|-----|-----|\n");
+
+            for combination in &self.combination_scores {
+                let stats = &combination.stats;
+                report.push_str(&format!(
+                    "| {} | {} | {} | {:.2}% | {:.2} | {:.2}% | {:.2}% |\n",
+                    combination.prompt_id,
+                    combination.agent_id,
+                    stats.samples,
+                    stats.mean,
+                    stats.std_dev,
+                    stats.min,
+                    stats.max
+                ));
+            }
+        }
+
         report.push_str("\n## Individual Run Results\n\n");
 
+        let repeated = self.combination_scores.iter().any(|c| c.stats.samples > 1);
         for run in &self.runs {
-            report.push_str(&format!("### {} - {}\n", run.prompt_id, run.agent_id));
+            if repeated {
+                report.push_str(&format!(
+                    "### {} - {} (sample {})\n",
+                    run.prompt_id, run.agent_id, run.sample
+                ));
+            } else {
+                report.push_str(&format!("### {} - {}\n", run.prompt_id, run.agent_id));
+            }
             report.push_str(&format!("- Status: {:?}\n", run.status));
             if let Some(score) = run.score {
                 report.push_str(&format!("- Score: {:.2}%\n", score));
@@ -423,6 +665,9 @@ impl EvaluationResults {
                     "- Tests: {}/{} passed\n",
                     test_results.passed, test_results.total
                 ));
+                for (group, (passed, total)) in test_results.group_counts() {
+                    report.push_str(&format!("  - {}: {}/{} passed\n", group, passed, total));
+                }
             }
             // Show turns used vs max iterations
             match (run.turns_used, run.max_iterations) {
@@ -437,6 +682,9 @@ impl EvaluationResults {
                 }
                 _ => {}
             }
+            if let Some(iterations) = run.iterations_used {
+                report.push_str(&format!("- Agent iterations: {}\n", iterations));
+            }
             // Show token usage
             if let Some(ref usage) = run.token_usage {
                 report.push_str(&format!(
@@ -449,7 +697,13 @@ impl EvaluationResults {
             if let Some(ref error) = run.error {
                 report.push_str(&format!("- Error: {}\n", error));
             }
-            report.push_str("\n");
+            if let Some(ref build_output) = run.build_output {
+                // The end of a build log is where the errors are
+                let lines: Vec<&str> = build_output.lines().collect();
+                let tail = &lines[lines.len().saturating_sub(20)..];
+                report.push_str(&format!("\n```text\n{}\n```\n", tail.join("\n")));
+            }
+            report.push('\n');
         }
 
         report
@@ -475,6 +729,36 @@ mod tests {
         assert_eq!(result.pass_rate(), 80.0);
     }
 
+    #[test]
+    fn test_skipped_tests_do_not_change_pass_rate() {
+        let test = |name: &str, passed: bool| TestCaseResult {
+            name: name.to_string(),
+            passed,
+            duration_ms: None,
+            error: None,
+            stdout: None,
+            weight: Some(2.0),
+            group: None,
+        };
+        let without_skips = TestSuiteResult {
+            total: 10,
+            passed: 8,
+            failed: 2,
+            skipped: 0,
+            tests: vec![test("a", true), test("b", false)],
+            duration_ms: 1000,
+            raw_output: String::new(),
+        };
+        let with_skips = TestSuiteResult {
+            skipped: 5,
+            ..without_skips.clone()
+        };
+
+        // 7 of 8 unlisted tests passed, plus 2 of 4 listed weight
+        assert_eq!(without_skips.pass_rate(), 75.0);
+        assert_eq!(with_skips.pass_rate(), without_skips.pass_rate());
+    }
+
     #[test]
     fn test_eval_run_result_complete() {
         let mut run = EvalRunResult::new("run-1", "prompt-1", "agent-1", "claude-code", "opus-4.5");
@@ -542,4 +826,101 @@ mod tests {
         assert_eq!(results.agent_scores[0].agent_id, "agent-1");
         assert_eq!(results.summary.best_agent, Some("agent-1".to_string()));
     }
+
+    #[test]
+    fn test_weighted_scores_rank_agents() {
+        let test = |name: &str, passed: bool, weight: f64| TestCaseResult {
+            name: name.to_string(),
+            passed,
+            duration_ms: None,
+            error: None,
+            stdout: None,
+            weight: Some(weight),
+            group: None,
+        };
+        let mut results = EvaluationResults::new("Test Eval", "eval-1");
+
+        // agent-1 passes more tests, agent-2 passes the one that counts
+        for (agent, passed) in [
+            ("agent-1", [true, true, false]),
+            ("agent-2", [false, false, true]),
+        ] {
+            let tests: Vec<TestCaseResult> = ["a", "b", "c"]
+                .into_iter()
+                .zip(passed)
+                .zip([1.0, 1.0, 7.0])
+                .map(|((name, passed), weight)| test(name, passed, weight))
+                .collect();
+            let passed = tests.iter().filter(|t| t.passed).count() as u32;
+            let mut run = EvalRunResult::new(agent, "prompt-1", agent, "claude-code", "opus-4.5");
+            run.complete_with_results(TestSuiteResult {
+                total: 3,
+                passed,
+                failed: 3 - passed,
+                skipped: 1,
+                tests,
+                duration_ms: 1000,
+                raw_output: String::new(),
+            });
+            results.add_run(run);
+        }
+        results.finalize();
+
+        // The skipped test doesn't count: 7 of 9 and 2 of 9
+        let best = &results.agent_scores[0];
+        assert_eq!(best.agent_id, "agent-2");
+        assert!((best.average_score - 700.0 / 9.0).abs() < 1e-9);
+        assert!((results.agent_scores[1].average_score - 200.0 / 9.0).abs() < 1e-9);
+        assert!((results.summary.overall_pass_rate - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_samples_are_aggregated_per_combination() {
+        let overrides = BTreeMap::from([("samples".to_string(), "3".to_string())]);
+        let mut results = EvaluationResults::new("Test Eval", "eval-1").with_overrides(overrides);
+
+        for (sample, passed) in [(1, 6), (2, 8), (3, 10)] {
+            let mut run = EvalRunResult::new(
+                &format!("run-{}", sample),
+                "prompt-1",
+                "agent-1",
+                "claude-code",
+                "opus-4.5",
+            )
+            .with_sample(sample);
+            run.complete_with_results(TestSuiteResult {
+                total: 10,
+                passed,
+                failed: 10 - passed,
+                skipped: 0,
+                tests: vec![],
+                duration_ms: 1000,
+                raw_output: String::new(),
+            });
+            results.add_run(run);
+        }
+        let mut failed =
+            EvalRunResult::new("run-4", "prompt-2", "agent-1", "claude-code", "opus-4.5");
+        failed.fail_with_error("Pod failed");
+        results.add_run(failed);
+        results.finalize();
+
+        let stats = &results.agent_scores[0].prompt_scores["prompt-1"];
+        assert_eq!(stats.samples, 3);
+        assert_eq!(stats.mean, 80.0);
+        assert_eq!(stats.std_dev, 20.0);
+        assert_eq!((stats.min, stats.max), (60.0, 100.0));
+
+        assert_eq!(results.combination_scores.len(), 2);
+        assert_eq!(results.combination_scores[1].prompt_id, "prompt-2");
+        assert_eq!(
+            results.combination_scores[1].stats,
+            ScoreStats::from_scores(&[0.0])
+        );
+
+        let report = results.generate_report();
+        assert!(report.contains("| prompt-1 | agent-1 | 3 | 80.00% | 20.00 | 60.00% | 100.00% |"));
+        assert!(report.contains("### prompt-1 - agent-1 (sample 3)"));
+        assert!(report.contains("Override: samples=3\n"));
+    }
 }
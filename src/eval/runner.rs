@@ -1,10 +1,15 @@
-use crate::agents::AgentConfig;
+use crate::agents::{shell_quote, AgentConfig};
 use crate::cli::{EvalConfig, PromptConfig, TestHarness};
-use crate::eval::{EvalRunResult, EvaluationResults, RunStatus, TestCaseResult, TestSuiteResult};
-use crate::kubernetes::{AgentPodConfig, PodManager, PodStatus};
+use crate::eval::{
+    parse_test_results, EvalRunResult, EvaluationResults, HarnessOutput, ResultsLayout, RunStatus,
+};
+use crate::kubernetes::{
+    archive_run_files, build_container_env, build_entrypoint_script, build_init_script,
+    AgentPodConfig, PodManager, PodStatus,
+};
 use anyhow::Result;
 use std::collections::BTreeMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
@@ -25,8 +30,12 @@ impl EvalRunner {
     pub async fn new(config: EvalConfig, namespace: &str) -> Result<Self> {
         let pod_manager = Arc::new(PodManager::new(namespace).await?);
         let api_keys = config.settings.api_keys.resolve()?;
+
         let eval_id = Uuid::new_v4().to_string();
-        let results = Arc::new(Mutex::new(EvaluationResults::new(&config.name, &eval_id)));
+        let results = EvaluationResults::new(&config.name, &eval_id)
+            .with_filter(config.filter.clone())
+            .with_overrides(config.overrides.clone());
+        let results = Arc::new(Mutex::new(results));
 
         Ok(Self {
             pod_manager,
@@ -49,18 +58,19 @@ impl EvalRunner {
             self.config.name, eval_id
         );
 
-        let combinations = self.config.combinations();
+        let runs = self.config.scheduled_runs();
         info!(
-            "Running {} combinations with parallelism {}",
-            combinations.len(),
+            "Running {} runs of {} combinations with parallelism {}",
+            runs.len(),
+            self.config.combinations().len(),
             parallelism
         );
 
-        // Process combinations with a semaphore for parallelism
+        // Process runs with a semaphore for parallelism
         let semaphore = Arc::new(tokio::sync::Semaphore::new(parallelism as usize));
         let mut handles = Vec::new();
 
-        for (prompt, agent) in combinations {
+        for (prompt, agent, sample) in runs {
             let permit = semaphore.clone().acquire_owned().await.unwrap();
             let pod_manager = Arc::clone(&self.pod_manager);
             let api_keys = self.api_keys.clone();
@@ -74,6 +84,7 @@ impl EvalRunner {
                     &pod_manager,
                     &prompt,
                     &agent,
+                    sample,
                     &api_keys,
                     &namespace,
                     timeout_hours,
@@ -107,36 +118,28 @@ impl EvalRunner {
     }
 
     /// Get the current results
+    #[allow(dead_code)]
     pub async fn results(&self) -> EvaluationResults {
         self.results.lock().await.clone()
     }
 
-    /// Save results to the output directory
-    pub async fn save_results(&self, output_dir: &Path) -> Result<()> {
-        std::fs::create_dir_all(output_dir)?;
-
+    /// Save results to the output directory (see [`ResultsLayout`]),
+    /// returning the evaluation's directory
+    pub async fn save_results(&self, output_dir: &Path) -> Result<PathBuf> {
         let results = self.results.lock().await;
-
-        // Save JSON results
-        let json_path = output_dir.join(format!("{}.json", results.eval_id));
-        results.save_json(&json_path)?;
-        info!("Saved results to {:?}", json_path);
-
-        // Save markdown report
-        let report_path = output_dir.join(format!("{}_report.md", results.eval_id));
-        let report = results.generate_report();
-        std::fs::write(&report_path, report)?;
-        info!("Saved report to {:?}", report_path);
-
-        Ok(())
+        let eval_dir = ResultsLayout::new(output_dir).save(&results)?;
+        info!("Saved results to {:?}", eval_dir);
+        Ok(eval_dir)
     }
 }
 
 /// Run a single (prompt, agent) combination
+#[allow(clippy::too_many_arguments)]
 async fn run_single_eval(
     pod_manager: &PodManager,
     prompt: &PromptConfig,
     agent: &AgentConfig,
+    sample: u32,
     api_keys: &BTreeMap<String, String>,
     namespace: &str,
     timeout_hours: u32,
@@ -147,52 +150,180 @@ async fn run_single_eval(
     let agent_id = agent.id();
 
     info!(
-        "Starting run {} for prompt '{}' with agent '{}'",
-        run_id, prompt.id, agent_id
+        "Starting run {} for prompt '{}' with agent '{}' (sample {})",
+        run_id, prompt.id, agent_id, sample
     );
 
     let mut result = EvalRunResult::new(
         &run_id,
         &prompt.id,
         &agent_id,
-        &agent.tool.to_string(),
+        &agent.tool_name(),
         &agent.model.to_string(),
-    );
+    )
+    .with_sample(sample);
+    if let Some(budget) = agent.total_turn_budget() {
+        result = result.with_max_iterations(budget);
+    }
     result.status = RunStatus::Running;
 
     let timeout = prompt.timeout_hours.unwrap_or(default_timeout).min(timeout_hours);
 
-    // Get test command from harness
-    let (test_cmd, test_args) = prompt.test_harness.test_command();
+    // Ship the eval's starter files (git sources are cloned there) and a fake
+    // agent's fixtures to the pod
+    let fixtures = agent.fake.as_ref().map(|fake| fake.fixtures.as_path());
+    let workspace_config_map = match pod_manager
+        .upload_workspace(&run_id, prompt.eval_path.as_deref(), fixtures)
+        .await
+    {
+        Ok(name) => name,
+        Err(e) => {
+            error!("Failed to upload workspace for run {}: {:#}", run_id, e);
+            result.fail_with_error(&format!("Failed to upload workspace: {:#}", e));
+            return result;
+        }
+    };
+
+    let pod_config = pod_config(
+        prompt,
+        agent,
+        &run_id,
+        namespace,
+        timeout,
+        api_keys,
+        workspace_config_map,
+    );
 
-    // Create pod configuration
-    let pod_config = AgentPodConfig {
+    let pod_name = run_pod(pod_manager, &pod_config, agent, prompt, &mut result).await;
+
+    // Every exit after the upload comes through here. The workspace is only
+    // kept alongside a pod kept for debugging.
+    if cleanup_on_complete || pod_name.is_none() {
+        if let Some(pod_name) = &pod_name {
+            if let Err(e) = pod_manager.delete_pod(pod_name).await {
+                warn!("Failed to cleanup pod {}: {}", pod_name, e);
+            }
+        }
+        if pod_config.workspace_config_map.is_some() {
+            if let Err(e) = pod_manager.delete_workspace(&run_id).await {
+                warn!("Failed to cleanup workspace for run {}: {}", run_id, e);
+            }
+        }
+    }
+
+    result
+}
+
+/// Configuration of the pod running `agent` on `prompt`
+fn pod_config(
+    prompt: &PromptConfig,
+    agent: &AgentConfig,
+    run_id: &str,
+    namespace: &str,
+    timeout_hours: u32,
+    api_keys: &BTreeMap<String, String>,
+    workspace_config_map: Option<String>,
+) -> AgentPodConfig {
+    let (test_command, test_args) = prompt.test_harness.test_command();
+    AgentPodConfig {
         agent: agent.clone(),
         prompt: prompt.prompt.clone(),
-        eval_path: prompt.eval_path.to_string_lossy().to_string(),
-        run_id: run_id.clone(),
+        eval_path: prompt.source(),
+        run_id: run_id.to_string(),
         namespace: namespace.to_string(),
-        timeout_hours: timeout,
+        timeout_hours,
         api_keys: api_keys.clone(),
-        test_command: test_cmd,
+        env: prompt.run_env(agent),
+        test_command,
         test_args,
-        git_repo: None, // TODO: Add git_repo support to PromptConfig
+        build_command: prompt.test_harness.build_command(),
+        test_report: prompt.test_harness.report().map(|r| r.path),
+        git_repo: prompt.git_repo.clone(),
         setup_commands: prompt.setup_commands.clone(),
+        workspace_config_map,
+    }
+}
+
+/// Write the pod of `config`'s only (prompt, agent) combination to `root`,
+/// with the pod's volumes under it, so its scripts can be run and debugged
+/// without Kubernetes: the workspace ConfigMap's tarballs, the init and
+/// entrypoint scripts, and `pod.sh`, which runs them as the pod would
+pub async fn write_pod_scripts(config: &EvalConfig, root: &Path) -> Result<()> {
+    let combinations = config.combinations();
+    let [(prompt, agent)] = combinations.as_slice() else {
+        anyhow::bail!(
+            "Select one (prompt, agent) combination with --prompt and --agent, not {}",
+            combinations.len()
+        );
     };
 
+    for dir in ["workspace", "results", "workspace-archive", "agent-fixtures"] {
+        std::fs::create_dir_all(root.join(dir))?;
+    }
+    let root = &std::fs::canonicalize(root)?;
+
+    let fixtures = agent.fake.as_ref().map(|fake| fake.fixtures.as_path());
+    for (key, archive) in archive_run_files(prompt.eval_path.as_deref(), fixtures).await? {
+        std::fs::write(root.join("workspace-archive").join(key), archive)?;
+    }
+
+    let pod_config = pod_config(
+        prompt,
+        agent,
+        &Uuid::new_v4().to_string(),
+        "local",
+        prompt
+            .timeout_hours
+            .unwrap_or(config.settings.default_timeout_hours),
+        &config.settings.api_keys.resolve()?,
+        None,
+    );
+    let init = root.join("init.sh");
+    let entrypoint = root.join("entrypoint.sh");
+    std::fs::write(&init, build_init_script(&pod_config, root))?;
+    std::fs::write(&entrypoint, build_entrypoint_script(&pod_config, root)?)?;
+
+    let quote = |path: &Path| shell_quote(&path.to_string_lossy());
+    let mut pod = format!(
+        "#!/bin/bash\n# The init container, then the agent container\nset -e\nsh {}\n",
+        quote(&init)
+    );
+    for (name, value) in build_container_env(&pod_config, root)? {
+        pod.push_str(&format!("export {}={}\n", name, shell_quote(&value)));
+    }
+    pod.push_str(&format!(
+        "cd {}\nexec bash {}\n",
+        quote(&root.join("workspace")),
+        quote(&entrypoint)
+    ));
+    std::fs::write(root.join("pod.sh"), pod)?;
+    Ok(())
+}
+
+/// Spawn the agent pod, wait for it and record its outcome on `result`,
+/// returning the pod's name if it was created
+async fn run_pod(
+    pod_manager: &PodManager,
+    pod_config: &AgentPodConfig,
+    agent: &AgentConfig,
+    prompt: &PromptConfig,
+    result: &mut EvalRunResult,
+) -> Option<String> {
+    let run_id = &pod_config.run_id;
+
     // Spawn the pod
-    let pod_name = match pod_manager.spawn_pod(&pod_config).await {
+    let pod_name = match pod_manager.spawn_pod(pod_config).await {
         Ok(name) => name,
         Err(e) => {
             error!("Failed to spawn pod for run {}: {}", run_id, e);
             result.fail_with_error(&format!("Failed to spawn pod: {}", e));
-            return result;
+            return None;
         }
     };
 
     // Wait for agent to complete
     let check_interval = Duration::from_secs(30);
-    let max_duration = Duration::from_secs((timeout * 3600) as u64);
+    let max_duration = Duration::from_secs((pod_config.timeout_hours * 3600) as u64);
 
     let pod_status = match pod_manager
         .wait_for_completion(&pod_name, check_interval, max_duration)
@@ -206,66 +337,131 @@ async fn run_single_eval(
             if let Ok(logs) = pod_manager.get_pod_logs(&pod_name).await {
                 result.agent_logs = Some(logs);
             }
-            return result;
+            return Some(pod_name);
         }
     };
 
-    // Get agent logs
-    if let Ok(logs) = pod_manager.get_pod_logs(&pod_name).await {
-        result.agent_logs = Some(logs);
+    let logs = pod_manager.get_pod_logs(&pod_name).await.ok();
+    record_pod_outcome(result, agent, &prompt.test_harness, pod_status, logs);
+    Some(pod_name)
+}
+
+/// Record a finished pod's outcome on `result`: the agent's turns and usage
+/// from its logs and, when the pod succeeded, the build or test results
+fn record_pod_outcome(
+    result: &mut EvalRunResult,
+    agent: &AgentConfig,
+    harness: &TestHarness,
+    pod_status: PodStatus,
+    logs: Option<String>,
+) {
+    let run_id = result.run_id.clone();
+    if let Some(logs) = &logs {
+        record_agent_output(result, agent, logs);
+        if let Some(iterations) = extract_iterations_used(logs) {
+            result.set_iterations_used(iterations);
+        }
     }
 
-    match pod_status {
-        PodStatus::Succeeded => {
+    match (pod_status, &logs) {
+        (PodStatus::Succeeded, Some(logs)) => {
             info!("Pod completed for run {}, parsing test results from logs", run_id);
 
-            // Get logs which contain test output
-            if let Ok(logs) = pod_manager.get_pod_logs(&pod_name).await {
-                result.agent_logs = Some(logs.clone());
-
-                // Extract test output from logs (between TEST_OUTPUT_START and TEST_OUTPUT_END)
-                if let Some(test_output) = extract_test_output(&logs) {
-                    match parse_test_output(&prompt.test_harness, &test_output) {
-                        Ok(test_results) => {
-                            result.complete_with_results(test_results);
-                            info!(
-                                "Run {} completed with score {:.2}%",
-                                run_id,
-                                result.score.unwrap_or(0.0)
-                            );
-                        }
-                        Err(e) => {
-                            error!("Failed to parse test results for run {}: {}", run_id, e);
-                            result.fail_with_error(&format!("Failed to parse test results: {}", e));
-                        }
+            // Extract test output from logs (between TEST_OUTPUT_START and TEST_OUTPUT_END)
+            if let Some(build_output) = extract_build_failure(logs) {
+                warn!("Build failed for run {}", run_id);
+                result.fail_build(&build_output);
+            } else if let (Some(124), Some(seconds)) =
+                (extract_test_exit_code(logs), harness.timeout_seconds())
+            {
+                // coreutils `timeout` exits with 124 when it kills the tests
+                warn!("Tests timed out for run {}", run_id);
+                result.fail_with_error(&format!("Tests timed out after {} seconds", seconds));
+                result.status = RunStatus::Timeout;
+            } else if let Some(test_output) = extract_test_output(logs) {
+                let output = HarnessOutput {
+                    console: test_output,
+                    results_file: extract_printed_file(logs, "ANODE_RESULTS"),
+                    report: extract_printed_file(logs, "TEST_REPORT"),
+                    duration_ms: extract_test_duration(logs),
+                };
+                match parse_test_results(harness, &output) {
+                    Ok(test_results) => {
+                        result.complete_with_results(test_results);
+                        info!(
+                            "Run {} completed with score {:.2}%",
+                            run_id,
+                            result.score.unwrap_or(0.0)
+                        );
+                    }
+                    Err(e) => {
+                        error!("Failed to parse test results for run {}: {}", run_id, e);
+                        result.fail_with_error(&format!("Failed to parse test results: {}", e));
                     }
-                } else {
-                    warn!("No test output found in logs for run {}", run_id);
-                    result.fail_with_error("No test output found in pod logs");
                 }
             } else {
-                error!("Failed to get logs for run {}", run_id);
-                result.fail_with_error("Failed to retrieve pod logs");
+                warn!("No test output found in logs for run {}", run_id);
+                result.fail_with_error("No test output found in pod logs");
             }
         }
-        PodStatus::Failed(reason) => {
+        (PodStatus::Succeeded, None) => {
+            error!("Failed to get logs for run {}", run_id);
+            result.fail_with_error("Failed to retrieve pod logs");
+        }
+        (PodStatus::Failed(reason), _) => {
             error!("Agent failed for run {}: {}", run_id, reason);
             result.fail_with_error(&reason);
         }
-        _ => {
+        (pod_status, _) => {
             warn!("Unexpected pod status for run {}: {:?}", run_id, pod_status);
             result.fail_with_error(&format!("Unexpected pod status: {:?}", pod_status));
         }
     }
+    result.agent_logs = logs;
+}
 
-    // Cleanup pod if configured
-    if cleanup_on_complete {
-        if let Err(e) = pod_manager.delete_pod(&pod_name).await {
-            warn!("Failed to cleanup pod {}: {}", pod_name, e);
-        }
+/// Record turn count and token usage reported in the agent's output
+pub(crate) fn record_agent_output(result: &mut EvalRunResult, agent: &AgentConfig, output: &str) {
+    let agent_output = agent.parse_output(output);
+
+    if let Some(turns) = agent_output.turns_used {
+        result.set_turns_used(turns);
     }
+    if let Some(usage) = agent_output.token_usage {
+        result.set_token_usage(usage);
+    }
+    if agent.adapter().is_ok_and(|a| a.hit_max_turns(output)) {
+        warn!(
+            "Agent {} hit its turn limit ({}) in run {}",
+            agent.id(),
+            agent.turns_per_invocation(),
+            result.run_id
+        );
+    }
+}
 
-    result
+/// Extract the number of agent invocations reported by the entrypoint script
+fn extract_iterations_used(logs: &str) -> Option<u32> {
+    logs.lines()
+        .rev()
+        .find_map(|line| line.trim().strip_prefix("ANODE_ITERATIONS_USED="))
+        .and_then(|n| n.trim().parse().ok())
+}
+
+/// Extract the test command's wall time reported by the entrypoint script
+fn extract_test_duration(logs: &str) -> Option<u64> {
+    logs.lines()
+        .rev()
+        .find_map(|line| line.trim().strip_prefix("ANODE_TEST_DURATION_MS="))
+        .and_then(|ms| ms.trim().parse().ok())
+}
+
+/// Extract the test command's exit code reported by the entrypoint script
+fn extract_test_exit_code(logs: &str) -> Option<i32> {
+    logs.lines()
+        .rev()
+        .find_map(|line| line.trim().strip_prefix("ANODE_TEST_EXIT_CODE="))
+        .and_then(|code| code.trim().parse().ok())
 }
 
 /// Extract test output from pod logs (between TEST_OUTPUT_START and TEST_OUTPUT_END markers)
@@ -282,334 +478,141 @@ fn extract_test_output(logs: &str) -> Option<String> {
     None
 }
 
-/// Parse test output based on the harness type
-fn parse_test_output(harness: &TestHarness, output: &str) -> Result<TestSuiteResult> {
-    match harness {
-        TestHarness::Cargo { .. } => parse_cargo_test_output(output),
-        TestHarness::Npm { .. } => parse_generic_test_output(output),
-        TestHarness::Pytest { .. } => parse_pytest_output(output),
-        TestHarness::Go { .. } => parse_go_test_output(output),
-        TestHarness::Custom { .. } => parse_generic_test_output(output),
-    }
-}
-
-/// Parse cargo test JSON output
-fn parse_cargo_test_output(output: &str) -> Result<TestSuiteResult> {
-    let mut tests = Vec::new();
-    let mut total = 0;
-    let mut passed = 0;
-    let mut failed = 0;
-
-    // Parse each line as JSON
-    for line in output.lines() {
-        if let Ok(json) = serde_json::from_str::<serde_json::Value>(line) {
-            if let Some(event_type) = json.get("type").and_then(|v| v.as_str()) {
-                if event_type == "test" {
-                    if let Some(event) = json.get("event").and_then(|v| v.as_str()) {
-                        if let Some(name) = json.get("name").and_then(|v| v.as_str()) {
-                            total += 1;
-                            let test_passed = event == "ok";
-                            if test_passed {
-                                passed += 1;
-                            } else {
-                                failed += 1;
-                            }
-
-                            tests.push(TestCaseResult {
-                                name: name.to_string(),
-                                passed: test_passed,
-                                duration_ms: json
-                                    .get("exec_time")
-                                    .and_then(|v| v.as_f64())
-                                    .map(|t| (t * 1000.0) as u64),
-                                error: if !test_passed {
-                                    json.get("stdout").and_then(|v| v.as_str()).map(String::from)
-                                } else {
-                                    None
-                                },
-                                stdout: json.get("stdout").and_then(|v| v.as_str()).map(String::from),
-                            });
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    // If JSON parsing didn't work, try plain text parsing
-    if tests.is_empty() {
-        return parse_cargo_test_plain(output);
-    }
-
-    Ok(TestSuiteResult {
-        total,
-        passed,
-        failed,
-        skipped: 0,
-        tests,
-        duration_ms: 0,
-        raw_output: output.to_string(),
-    })
-}
-
-/// Parse cargo test plain text output
-fn parse_cargo_test_plain(output: &str) -> Result<TestSuiteResult> {
-    let mut tests = Vec::new();
-    let mut total = 0;
-    let mut passed = 0;
-    let mut failed = 0;
-
-    for line in output.lines() {
-        if line.starts_with("test ") && (line.contains(" ... ok") || line.contains(" ... FAILED")) {
-            total += 1;
-            let test_passed = line.contains(" ... ok");
-            if test_passed {
-                passed += 1;
-            } else {
-                failed += 1;
-            }
-
-            let name = line
-                .strip_prefix("test ")
-                .and_then(|s| s.split(" ... ").next())
-                .unwrap_or("unknown")
-                .to_string();
-
-            tests.push(TestCaseResult {
-                name,
-                passed: test_passed,
-                duration_ms: None,
-                error: None,
-                stdout: None,
-            });
-        }
-    }
-
-    Ok(TestSuiteResult {
-        total,
-        passed,
-        failed,
-        skipped: 0,
-        tests,
-        duration_ms: 0,
-        raw_output: output.to_string(),
-    })
-}
-
-/// Parse pytest output
-fn parse_pytest_output(output: &str) -> Result<TestSuiteResult> {
-    let mut tests = Vec::new();
-    let mut total = 0;
-    let mut passed = 0;
-    let mut failed = 0;
-    let mut skipped = 0;
-
-    for line in output.lines() {
-        if line.contains("PASSED") {
-            total += 1;
-            passed += 1;
-            let name = line.split("::").last().unwrap_or("unknown").trim();
-            let name = name.split_whitespace().next().unwrap_or(name);
-            tests.push(TestCaseResult {
-                name: name.to_string(),
-                passed: true,
-                duration_ms: None,
-                error: None,
-                stdout: None,
-            });
-        } else if line.contains("FAILED") {
-            total += 1;
-            failed += 1;
-            let name = line.split("::").last().unwrap_or("unknown").trim();
-            let name = name.split_whitespace().next().unwrap_or(name);
-            tests.push(TestCaseResult {
-                name: name.to_string(),
-                passed: false,
-                duration_ms: None,
-                error: Some("Test failed".to_string()),
-                stdout: None,
-            });
-        } else if line.contains("SKIPPED") {
-            total += 1;
-            skipped += 1;
-        }
-    }
-
-    Ok(TestSuiteResult {
-        total,
-        passed,
-        failed,
-        skipped,
-        tests,
-        duration_ms: 0,
-        raw_output: output.to_string(),
-    })
-}
-
-/// Parse go test output
-fn parse_go_test_output(output: &str) -> Result<TestSuiteResult> {
-    let mut tests = Vec::new();
-    let mut total = 0;
-    let mut passed = 0;
-    let mut failed = 0;
-
-    for line in output.lines() {
-        if line.starts_with("--- PASS:") {
-            total += 1;
-            passed += 1;
-            let name = line
-                .strip_prefix("--- PASS: ")
-                .and_then(|s| s.split_whitespace().next())
-                .unwrap_or("unknown")
-                .to_string();
-            tests.push(TestCaseResult {
-                name,
-                passed: true,
-                duration_ms: None,
-                error: None,
-                stdout: None,
-            });
-        } else if line.starts_with("--- FAIL:") {
-            total += 1;
-            failed += 1;
-            let name = line
-                .strip_prefix("--- FAIL: ")
-                .and_then(|s| s.split_whitespace().next())
-                .unwrap_or("unknown")
-                .to_string();
-            tests.push(TestCaseResult {
-                name,
-                passed: false,
-                duration_ms: None,
-                error: Some("Test failed".to_string()),
-                stdout: None,
-            });
-        }
-    }
-
-    Ok(TestSuiteResult {
-        total,
-        passed,
-        failed,
-        skipped: 0,
-        tests,
-        duration_ms: 0,
-        raw_output: output.to_string(),
-    })
+/// Extract a file the entrypoint printed between `{marker}_START` and
+/// `{marker}_END` lines (the harness's report file or results file)
+fn extract_printed_file(logs: &str, marker: &str) -> Option<String> {
+    // The files are printed after the test output, which may mention the
+    // markers itself
+    let after_tests = logs.rfind("TEST_OUTPUT_END").map_or(logs, |i| &logs[i..]);
+    let start_marker = format!("{}_START", marker);
+    let start_idx = after_tests.find(&start_marker)?;
+    let after_start = &after_tests[start_idx + start_marker.len()..];
+    let end_idx = after_start.find(&format!("{}_END", marker))?;
+    Some(after_start[..end_idx].trim().to_string())
 }
 
-/// Generic test output parser (for custom harnesses)
-fn parse_generic_test_output(output: &str) -> Result<TestSuiteResult> {
-    // Try to extract test counts from common patterns
-    let mut total = 0;
-    let mut passed = 0;
-    let mut failed = 0;
-
-    // Look for common summary patterns
-    for line in output.lines() {
-        let line_lower = line.to_lowercase();
-
-        // Pattern: "X passed, Y failed"
-        if line_lower.contains("passed") && line_lower.contains("failed") {
-            let parts: Vec<&str> = line.split(|c: char| !c.is_numeric()).collect();
-            let nums: Vec<u32> = parts.iter().filter_map(|s| s.parse().ok()).collect();
-
-            if nums.len() >= 2 {
-                passed = nums[0];
-                failed = nums[1];
-                total = passed + failed;
-                break;
-            }
-        }
-
-        // Pattern: "Tests: X passed, Y failed, Z total"
-        if line_lower.contains("tests:") {
-            let parts: Vec<&str> = line.split(|c: char| !c.is_numeric()).collect();
-            let nums: Vec<u32> = parts.iter().filter_map(|s| s.parse().ok()).collect();
-
-            if !nums.is_empty() {
-                for (i, num) in nums.iter().enumerate() {
-                    if i == 0 {
-                        passed = *num;
-                    } else if i == 1 {
-                        failed = *num;
-                    } else if i == 2 {
-                        total = *num;
-                    }
-                }
-                if total == 0 {
-                    total = passed + failed;
-                }
-                break;
-            }
-        }
+/// Extract the build output from pod logs if the entrypoint reported that the
+/// harness's build step failed
+fn extract_build_failure(logs: &str) -> Option<String> {
+    if !logs.lines().any(|line| line.trim() == "ANODE_BUILD_FAILED") {
+        return None;
     }
 
-    Ok(TestSuiteResult {
-        total,
-        passed,
-        failed,
-        skipped: 0,
-        tests: vec![],
-        duration_ms: 0,
-        raw_output: output.to_string(),
-    })
+    let start_marker = "BUILD_OUTPUT_START";
+    let end_marker = "BUILD_OUTPUT_END";
+    let start_idx = logs.find(start_marker)?;
+    let after_start = &logs[start_idx + start_marker.len()..];
+    let end_idx = after_start.find(end_marker)?;
+    Some(after_start[..end_idx].trim().to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::agents::ModelVersion;
 
+    /// Parser-only: the logs were captured from the fake agent's pod scripts,
+    /// which `test_fake_agent_pod_scripts` in tests/fake_agent.rs runs
     #[test]
-    fn test_parse_cargo_test_plain() {
-        let output = r#"
-running 3 tests
-test tests::test_one ... ok
-test tests::test_two ... ok
-test tests::test_three ... FAILED
-
-failures:
-
-failures:
-    tests::test_three
+    fn test_record_pod_outcome() {
+        let agent = AgentConfig::fake(
+            "tests/fixtures/hello_world",
+            ModelVersion::new("claude_sonnet_4"),
+        );
+        let harness: TestHarness = serde_yaml::from_str("!cargo\ntimeout_seconds: 600").unwrap();
+        let new_result = || EvalRunResult::new("run", "hello-world", &agent.id(), "fake", "m");
+        let pod_logs =
+            include_str!("../../tests/fixtures/pod_logs/fake_agent_hello_world.log").to_string();
+
+        let mut result = new_result();
+        let logs = pod_logs.clone();
+        let status = PodStatus::Succeeded;
+        record_pod_outcome(&mut result, &agent, &harness, status, Some(logs));
+        assert_eq!(result.status, RunStatus::Completed, "{}", pod_logs);
+        assert_eq!(result.score, Some(100.0), "{}", pod_logs);
+        assert_eq!(result.turns_used, Some(3));
+        assert_eq!(result.iterations_used, Some(1));
+        assert_eq!(result.token_usage.as_ref().unwrap().input_tokens, 1200);
+        let tests = result.test_results.as_ref().unwrap();
+        assert_eq!((tests.total, tests.passed), (8, 8));
+        assert!(tests.duration_ms > 0);
+        assert!(result.agent_logs.is_some());
+
+        // A failed pod keeps the agent's usage but has no test results
+        let mut result = new_result();
+        let logs = pod_logs.clone();
+        let status = PodStatus::Failed("OOMKilled".to_string());
+        record_pod_outcome(&mut result, &agent, &harness, status, Some(logs));
+        assert_eq!(result.status, RunStatus::Failed);
+        assert_eq!(result.turns_used, Some(3));
+        assert!(result.test_results.is_none());
+
+        // Tests killed by the harness's time limit
+        let mut result = new_result();
+        let logs = pod_logs.replace("EXIT_CODE=0", "EXIT_CODE=124");
+        let status = PodStatus::Succeeded;
+        record_pod_outcome(&mut result, &agent, &harness, status, Some(logs));
+        assert_eq!(result.status, RunStatus::Timeout);
+        assert_eq!(
+            result.error.as_deref(),
+            Some("Tests timed out after 600 seconds")
+        );
+    }
 
-test result: FAILED. 2 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out
-"#;
+    #[test]
+    fn test_extract_iterations_used() {
+        let logs = "=== Agent iteration 1 of 3 ===\nANODE_ITERATIONS_USED=2\nTEST_OUTPUT_START\n";
+        assert_eq!(extract_iterations_used(logs), Some(2));
+        assert_eq!(extract_iterations_used("no marker"), None);
+    }
 
-        let result = parse_cargo_test_plain(output).unwrap();
-        assert_eq!(result.total, 3);
-        assert_eq!(result.passed, 2);
-        assert_eq!(result.failed, 1);
+    #[test]
+    fn test_extract_test_duration() {
+        let logs = "TEST_OUTPUT_START\nok\nTEST_OUTPUT_END\nANODE_TEST_DURATION_MS=1520\n";
+        assert_eq!(extract_test_duration(logs), Some(1520));
+        assert_eq!(
+            extract_test_duration("TEST_OUTPUT_START\nok\nTEST_OUTPUT_END"),
+            None
+        );
     }
 
     #[test]
-    fn test_parse_pytest_output() {
-        let output = r#"
-test_example.py::test_one PASSED
-test_example.py::test_two PASSED
-test_example.py::test_three FAILED
-"#;
-
-        let result = parse_pytest_output(output).unwrap();
-        assert_eq!(result.total, 3);
-        assert_eq!(result.passed, 2);
-        assert_eq!(result.failed, 1);
+    fn test_extract_build_failure() {
+        let logs = "=== ANODE-EVAL Build ===\nBUILD_OUTPUT_START\nerror[E0425]: cannot find value `x`\nBUILD_OUTPUT_END\nANODE_BUILD_FAILED\n";
+        assert_eq!(
+            extract_build_failure(logs).as_deref(),
+            Some("error[E0425]: cannot find value `x`")
+        );
+        assert_eq!(
+            extract_build_failure("TEST_OUTPUT_START\nok\nTEST_OUTPUT_END"),
+            None
+        );
     }
 
     #[test]
-    fn test_parse_go_test_output() {
-        let output = r#"
-=== RUN   TestOne
---- PASS: TestOne (0.00s)
-=== RUN   TestTwo
---- PASS: TestTwo (0.00s)
-=== RUN   TestThree
---- FAIL: TestThree (0.00s)
-"#;
-
-        let result = parse_go_test_output(output).unwrap();
-        assert_eq!(result.total, 3);
-        assert_eq!(result.passed, 2);
-        assert_eq!(result.failed, 1);
+    fn test_extract_printed_file() {
+        let logs = "TEST_OUTPUT_START\n1 passed\nTEST_OUTPUT_END\nANODE_RESULTS_START\n{\"tests\": []}\n\nANODE_RESULTS_END\nTEST_REPORT_START\n<testsuites/>\n\nTEST_REPORT_END\n";
+        assert_eq!(
+            extract_printed_file(logs, "ANODE_RESULTS").as_deref(),
+            Some("{\"tests\": []}")
+        );
+        assert_eq!(
+            extract_printed_file(logs, "TEST_REPORT").as_deref(),
+            Some("<testsuites/>")
+        );
+        assert_eq!(
+            extract_printed_file("TEST_OUTPUT_START\nok\nTEST_OUTPUT_END", "TEST_REPORT"),
+            None
+        );
+
+        // Markers in the agent's or the tests' output are not the file
+        let logs = format!(
+            "echo TEST_REPORT_START\nTEST_OUTPUT_START\nTEST_REPORT_END\n{}",
+            logs
+        );
+        assert_eq!(
+            extract_printed_file(&logs, "TEST_REPORT").as_deref(),
+            Some("<testsuites/>")
+        );
     }
 }
@@ -1,5 +1,7 @@
 mod pod_manager;
 mod pod_spec;
+mod workspace;
 
 pub use pod_manager::*;
 pub use pod_spec::*;
+pub use workspace::*;
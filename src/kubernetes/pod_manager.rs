@@ -1,10 +1,14 @@
 use crate::kubernetes::pod_spec::{build_agent_pod, AgentPodConfig};
+use crate::kubernetes::workspace::{
+    archive_run_files, build_workspace_config_map, workspace_config_map_name,
+};
 use anyhow::{Context, Result};
-use k8s_openapi::api::core::v1::Pod;
+use k8s_openapi::api::core::v1::{ConfigMap, Pod};
 use kube::{
     api::{Api, DeleteParams, ListParams, LogParams, PostParams},
     Client,
 };
+use std::path::Path;
 use std::time::Duration;
 use tokio::time::{interval, timeout};
 use tracing::{debug, error, info, warn};
@@ -62,7 +66,7 @@ impl PodManager {
     /// Spawn a new agent pod
     pub async fn spawn_pod(&self, config: &AgentPodConfig) -> Result<String> {
         let pods: Api<Pod> = Api::namespaced(self.client.clone(), &self.namespace);
-        let pod = build_agent_pod(config);
+        let pod = build_agent_pod(config)?;
         let pod_name = config.pod_name();
 
         info!("Creating pod: {}", pod_name);
@@ -75,6 +79,54 @@ impl PodManager {
         Ok(pod_name)
     }
 
+    /// Upload the contents of `eval_path` and a fake agent's `fixtures` for a
+    /// run, returning the name of the ConfigMap the pod unpacks them from, or
+    /// `None` if there is nothing to upload
+    pub async fn upload_workspace(
+        &self,
+        run_id: &str,
+        eval_path: Option<&Path>,
+        fixtures: Option<&Path>,
+    ) -> Result<Option<String>> {
+        let config_maps: Api<ConfigMap> = Api::namespaced(self.client.clone(), &self.namespace);
+        let archives = archive_run_files(eval_path, fixtures).await?;
+        if archives.is_empty() {
+            return Ok(None);
+        }
+        let name = workspace_config_map_name(run_id);
+
+        info!(
+            "Uploading workspace {:?} and fixtures {:?} ({} bytes) to ConfigMap {}",
+            eval_path,
+            fixtures,
+            archives.values().map(Vec::len).sum::<usize>(),
+            name
+        );
+
+        config_maps
+            .create(
+                &PostParams::default(),
+                &build_workspace_config_map(run_id, &self.namespace, archives),
+            )
+            .await
+            .context(format!("Failed to create ConfigMap: {}", name))?;
+
+        Ok(Some(name))
+    }
+
+    /// Delete the workspace ConfigMap of a run
+    pub async fn delete_workspace(&self, run_id: &str) -> Result<()> {
+        let config_maps: Api<ConfigMap> = Api::namespaced(self.client.clone(), &self.namespace);
+        let name = workspace_config_map_name(run_id);
+
+        config_maps
+            .delete(&name, &DeleteParams::default())
+            .await
+            .context(format!("Failed to delete ConfigMap: {}", name))?;
+
+        Ok(())
+    }
+
     /// Get the status of a pod
     pub async fn get_pod_status(&self, pod_name: &str) -> Result<PodStatus> {
         let pods: Api<Pod> = Api::namespaced(self.client.clone(), &self.namespace);
@@ -235,11 +287,19 @@ impl PodManager {
             }
         }
 
+        let config_maps: Api<ConfigMap> = Api::namespaced(self.client.clone(), &self.namespace);
+        let lp = ListParams::default().labels(&format!("run-id={}", run_id));
+        config_maps
+            .delete_collection(&DeleteParams::default(), &lp)
+            .await
+            .context("Failed to delete workspace ConfigMaps")?;
+
         Ok(())
     }
 
     /// Execute a command in a running pod and get output
     /// This is used to run the eval suite after the agent completes
+    #[allow(dead_code)]
     pub async fn exec_in_pod(&self, pod_name: &str, command: Vec<String>) -> Result<String> {
         // Note: For now, we'll use kubectl exec via subprocess
         // In production, you'd want to use the kube-rs exec API
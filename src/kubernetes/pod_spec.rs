@@ -1,90 +1,200 @@
-use crate::agents::{AgentConfig, AgentTool};
+use super::{FIXTURES_ARCHIVE_KEY, WORKSPACE_ARCHIVE_KEY};
+use crate::agents::{shell_quote, AgentConfig};
+use crate::cli::GitRepoConfig;
+use crate::eval::RESULTS_FILE_ENV;
+use anyhow::Result;
 use k8s_openapi::api::core::v1::{
-    Container, EnvVar, Pod, PodSpec, ResourceRequirements, SecurityContext, VolumeMount,
+    ConfigMapVolumeSource, Container, EnvVar, Pod, PodSpec, ResourceRequirements, SecurityContext,
+    Volume, VolumeMount,
 };
 use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
 use kube::api::ObjectMeta;
 use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Where the pod's volumes are mounted under its root: `/` in the pod, another
+/// directory when its scripts run outside Kubernetes
+struct PodDirs {
+    workspace: String,
+    results: String,
+    /// The workspace ConfigMap's tarballs
+    archive: String,
+    /// Where a fake agent's fixtures are unpacked
+    fixtures: String,
+}
+
+impl PodDirs {
+    fn new(root: &Path) -> Self {
+        let dir = |name: &str| root.join(name).to_string_lossy().into_owned();
+        Self {
+            workspace: dir("workspace"),
+            results: dir("results"),
+            archive: dir("workspace-archive"),
+            fixtures: dir("agent-fixtures"),
+        }
+    }
+
+    /// Where the entrypoint writes the prompt for agents that read it from a file
+    fn prompt_file(&self) -> String {
+        format!("{}/prompt.txt", self.results)
+    }
+
+    /// Where harnesses may write machine-readable results (`$ANODE_RESULTS_FILE`)
+    fn results_file(&self) -> String {
+        format!("{}/test-results.json", self.results)
+    }
+}
 
 /// Configuration for creating an agent pod
 #[derive(Debug, Clone)]
 pub struct AgentPodConfig {
     pub agent: AgentConfig,
     pub prompt: String,
+    /// Where the workspace comes from (eval_path or git repo), for display
     pub eval_path: String,
     pub run_id: String,
     pub namespace: String,
     pub timeout_hours: u32,
     pub api_keys: BTreeMap<String, String>,
+    /// Environment from the prompt's and agent's `env`
+    pub env: BTreeMap<String, String>,
     /// Test command to run after agent completes (e.g., "cargo test")
     pub test_command: String,
     /// Test command arguments
     pub test_args: Vec<String>,
+    /// Build command and arguments to run before the tests, if the harness
+    /// has a separate build step
+    pub build_command: Option<(String, Vec<String>)>,
+    /// Report file the tests write, relative to the workspace unless absolute,
+    /// printed after the test output so the runner can parse it
+    pub test_report: Option<PathBuf>,
     /// Optional git repo to clone for the workspace
-    pub git_repo: Option<String>,
+    pub git_repo: Option<GitRepoConfig>,
     /// Setup commands to run before the agent
     pub setup_commands: Vec<String>,
+    /// ConfigMap holding the eval_path tarball to unpack into /workspace, and
+    /// a fake agent's fixtures to unpack into /agent-fixtures
+    pub workspace_config_map: Option<String>,
 }
 
+/// Longest pod name, so it is also a valid hostname
+const MAX_POD_NAME_LEN: usize = 63;
+
+/// Longest label value
+const MAX_LABEL_LEN: usize = 63;
+
 impl AgentPodConfig {
     /// Generate the pod name
     pub fn pod_name(&self) -> String {
+        let run_id = &self.run_id[..8];
+        let max_agent_len = MAX_POD_NAME_LEN - "anode-eval--".len() - run_id.len();
         format!(
             "anode-eval-{}-{}",
-            self.agent.id().replace('.', "-").to_lowercase(),
-            &self.run_id[..8]
+            kube_name(&self.agent.id(), max_agent_len),
+            run_id
         )
     }
 }
 
-/// Build a Kubernetes Pod specification for running an agent
-pub fn build_agent_pod(config: &AgentPodConfig) -> Pod {
-    let pod_name = config.pod_name();
+/// Make `value` usable in a pod name or label value: lowercase letters, digits
+/// and `-` only, at most `max_len` long, starting and ending with a letter or
+/// digit
+fn kube_name(value: &str, max_len: usize) -> String {
+    let name: String = value
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_lowercase() || c.is_ascii_digit() {
+                c
+            } else {
+                '-'
+            }
+        })
+        .take(max_len)
+        .collect();
+    name.trim_matches('-').to_string()
+}
 
-    // Build environment variables for API keys
-    let mut env_vars: Vec<EnvVar> = config
+/// Build the agent container's environment, with the pod's volumes under
+/// `root`: the API keys, the configured env and the run configuration
+pub fn build_container_env(config: &AgentPodConfig, root: &Path) -> Result<Vec<(String, String)>> {
+    if let Some(key) = config
+        .env
+        .keys()
+        .find(|key| config.api_keys.contains_key(*key))
+    {
+        anyhow::bail!("`{}` is set both as an API key and in `env`", key);
+    }
+    let mut env: Vec<(String, String)> = config
         .api_keys
         .iter()
-        .map(|(key, value)| EnvVar {
-            name: key.clone(),
-            value: Some(value.clone()),
-            value_from: None,
-        })
+        .chain(&config.env)
+        .map(|(key, value)| (key.clone(), value.clone()))
         .collect();
 
     // Add run configuration as env vars
-    env_vars.push(EnvVar {
-        name: "ANODE_RUN_ID".to_string(),
-        value: Some(config.run_id.clone()),
-        value_from: None,
-    });
-    env_vars.push(EnvVar {
-        name: "ANODE_AGENT_TOOL".to_string(),
-        value: Some(config.agent.tool.to_string()),
-        value_from: None,
-    });
-    env_vars.push(EnvVar {
-        name: "ANODE_MODEL".to_string(),
-        value: Some(config.agent.model.to_string()),
-        value_from: None,
-    });
-    env_vars.push(EnvVar {
-        name: "ANODE_ITERATIONS".to_string(),
-        value: Some(config.agent.iterations.to_string()),
-        value_from: None,
-    });
-    env_vars.push(EnvVar {
-        name: "ANODE_TIMEOUT_HOURS".to_string(),
-        value: Some(config.timeout_hours.to_string()),
-        value_from: None,
-    });
+    env.extend([
+        ("ANODE_RUN_ID".to_string(), config.run_id.clone()),
+        ("ANODE_AGENT_TOOL".to_string(), config.agent.tool_name()),
+        ("ANODE_MODEL".to_string(), config.agent.model.to_string()),
+        (
+            "ANODE_ITERATIONS".to_string(),
+            config.agent.turns_per_invocation().to_string(),
+        ),
+        (
+            "ANODE_TIMEOUT_HOURS".to_string(),
+            config.timeout_hours.to_string(),
+        ),
+        (
+            RESULTS_FILE_ENV.to_string(),
+            PodDirs::new(root).results_file(),
+        ),
+    ]);
+    Ok(env)
+}
+
+/// Build a Kubernetes Pod specification for running an agent
+pub fn build_agent_pod(config: &AgentPodConfig) -> Result<Pod> {
+    let pod_name = config.pod_name();
+    let root = Path::new("/");
+    let dirs = PodDirs::new(root);
+
+    let env_vars: Vec<EnvVar> = build_container_env(config, root)?
+        .into_iter()
+        .map(|(name, value)| EnvVar {
+            name,
+            value: Some(value),
+            value_from: None,
+        })
+        .collect();
 
     // Build the entrypoint script that will:
     // 1. Install the agent CLI
     // 2. Clone/setup the workspace
     // 3. Run the agent with the prompt
     // 4. Signal completion
-    let entrypoint_script = build_entrypoint_script(config);
+    let entrypoint_script = build_entrypoint_script(config, root)?;
+
+    let mut volume_mounts = vec![
+        VolumeMount {
+            name: "workspace".to_string(),
+            mount_path: dirs.workspace.clone(),
+            ..Default::default()
+        },
+        VolumeMount {
+            name: "results".to_string(),
+            mount_path: dirs.results.clone(),
+            ..Default::default()
+        },
+    ];
+    let fixtures_mount = VolumeMount {
+        name: "fixtures".to_string(),
+        mount_path: dirs.fixtures.clone(),
+        ..Default::default()
+    };
+    if config.agent.fake.is_some() {
+        volume_mounts.push(fixtures_mount.clone());
+    }
 
     let container = Container {
         name: "agent".to_string(),
@@ -109,35 +219,85 @@ pub fn build_agent_pod(config: &AgentPodConfig) -> Pod {
             run_as_user: Some(1000),
             ..Default::default()
         }),
-        volume_mounts: Some(vec![
+        volume_mounts: Some(volume_mounts),
+        working_dir: Some(dirs.workspace.clone()),
+        ..Default::default()
+    };
+
+    let mut volumes = vec![
+        Volume {
+            name: "workspace".to_string(),
+            empty_dir: Some(Default::default()),
+            ..Default::default()
+        },
+        Volume {
+            name: "results".to_string(),
+            empty_dir: Some(Default::default()),
+            ..Default::default()
+        },
+    ];
+    if config.agent.fake.is_some() {
+        volumes.push(Volume {
+            name: "fixtures".to_string(),
+            empty_dir: Some(Default::default()),
+            ..Default::default()
+        });
+    }
+
+    // Unpack the eval's starter files into the workspace, and a fake agent's
+    // fixtures, before the agent runs
+    let init_containers = config.workspace_config_map.as_ref().map(|config_map| {
+        volumes.push(Volume {
+            name: "workspace-archive".to_string(),
+            config_map: Some(ConfigMapVolumeSource {
+                name: Some(config_map.clone()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+
+        let mut volume_mounts = vec![
             VolumeMount {
                 name: "workspace".to_string(),
-                mount_path: "/workspace".to_string(),
+                mount_path: dirs.workspace.clone(),
                 ..Default::default()
             },
             VolumeMount {
-                name: "results".to_string(),
-                mount_path: "/results".to_string(),
+                name: "workspace-archive".to_string(),
+                mount_path: dirs.archive.clone(),
+                read_only: Some(true),
                 ..Default::default()
             },
-        ]),
-        working_dir: Some("/workspace".to_string()),
-        ..Default::default()
-    };
+        ];
+        if config.agent.fake.is_some() {
+            volume_mounts.push(fixtures_mount);
+        }
+
+        vec![Container {
+            name: "workspace".to_string(),
+            image: container.image.clone(),
+            image_pull_policy: container.image_pull_policy.clone(),
+            command: Some(vec!["/bin/sh".to_string(), "-c".to_string()]),
+            args: Some(vec![build_init_script(config, root)]),
+            security_context: container.security_context.clone(),
+            volume_mounts: Some(volume_mounts),
+            ..Default::default()
+        }]
+    });
 
     let mut labels = BTreeMap::new();
     labels.insert("app".to_string(), "anode-eval".to_string());
     labels.insert("run-id".to_string(), config.run_id.clone());
     labels.insert(
         "agent".to_string(),
-        config.agent.id().replace('.', "-").to_lowercase(),
+        kube_name(&config.agent.id(), MAX_LABEL_LEN),
     );
 
     let mut annotations = BTreeMap::new();
     annotations.insert("anode-eval/prompt".to_string(), config.prompt.clone());
     annotations.insert("anode-eval/eval-path".to_string(), config.eval_path.clone());
 
-    Pod {
+    Ok(Pod {
         metadata: ObjectMeta {
             name: Some(pod_name),
             namespace: Some(config.namespace.clone()),
@@ -147,69 +307,130 @@ pub fn build_agent_pod(config: &AgentPodConfig) -> Pod {
         },
         spec: Some(PodSpec {
             containers: vec![container],
+            init_containers,
             restart_policy: Some("Never".to_string()),
             // 6 hour timeout by default
             active_deadline_seconds: Some((config.timeout_hours * 3600) as i64),
-            volumes: Some(vec![
-                k8s_openapi::api::core::v1::Volume {
-                    name: "workspace".to_string(),
-                    empty_dir: Some(Default::default()),
-                    ..Default::default()
-                },
-                k8s_openapi::api::core::v1::Volume {
-                    name: "results".to_string(),
-                    empty_dir: Some(Default::default()),
-                    ..Default::default()
-                },
-            ]),
+            volumes: Some(volumes),
             ..Default::default()
         }),
         status: None,
+    })
+}
+
+/// Build the init container's script, with the pod's volumes under `root`:
+/// it unpacks the workspace ConfigMap's tarballs
+pub fn build_init_script(config: &AgentPodConfig, root: &Path) -> String {
+    let dirs = PodDirs::new(root);
+    let archive = |key: &str| shell_quote(&format!("{}/{}", dirs.archive, key));
+
+    // Git sources have no workspace tarball
+    let mut unpack = vec![format!(
+        "if [ -f {archive} ]; then tar -xzf {archive} -C {workspace}; fi",
+        archive = archive(WORKSPACE_ARCHIVE_KEY),
+        workspace = shell_quote(&dirs.workspace)
+    )];
+    if config.agent.fake.is_some() {
+        unpack.push(format!(
+            "tar -xzf {} -C {}",
+            archive(FIXTURES_ARCHIVE_KEY),
+            shell_quote(&dirs.fixtures)
+        ));
     }
+    unpack.join("\n")
 }
 
-/// Build the entrypoint script for the agent container
-fn build_entrypoint_script(config: &AgentPodConfig) -> String {
-    let install_cmd = config.agent.tool.install_command();
-    let cli_cmd = config.agent.tool.cli_command();
-    let model = config.agent.model.to_string();
-    let iterations = config.agent.iterations;
-
-    // Escape the prompt for shell
-    let escaped_prompt = config.prompt.replace('\'', "'\\''");
-
-    let run_command = match config.agent.tool {
-        AgentTool::ClaudeCode => {
-            // Claude Code: --max-turns for iterations, --dangerously-skip-permissions for non-interactive
-            // -p for prompt (non-interactive mode)
-            format!(
-                r#"{cli_cmd} --model {model} --max-turns {iterations} --dangerously-skip-permissions -p '{escaped_prompt}'"#,
-            )
-        }
-        AgentTool::Codex => {
-            // Codex: --full-auto for non-interactive mode with iterations
-            format!(
-                r#"{cli_cmd} --model {model} --full-auto --iterations {iterations} '{escaped_prompt}'"#,
-            )
-        }
-        AgentTool::OpenCode => {
-            // OpenCode: --auto-edit for non-interactive mode
-            format!(
-                r#"{cli_cmd} --model {model} --auto-edit --max-iterations {iterations} '{escaped_prompt}'"#,
-            )
-        }
-    };
+/// Build the entrypoint script for the agent container, with the pod's
+/// volumes under `root`
+pub fn build_entrypoint_script(config: &AgentPodConfig, root: &Path) -> Result<String> {
+    let dirs = PodDirs::new(root);
+    let prompt_file = dirs.prompt_file();
+    let results_file = shell_quote(&dirs.results_file());
+    let results = shell_quote(&dirs.results);
+    let workspace = shell_quote(&dirs.workspace);
+
+    // A fake agent replays the fixtures the init container unpacked
+    let mut agent = config.agent.clone();
+    if let Some(fake) = &mut agent.fake {
+        fake.fixtures = PathBuf::from(&dirs.fixtures);
+    }
+    let adapter = agent.adapter()?;
+    let install_cmd = adapter.install_command();
+    let max_invocations = config.agent.max_invocations();
+
+    let run_command = adapter
+        .invocation(
+            &config
+                .agent
+                .invocation(&config.prompt, Path::new(&prompt_file)),
+        )
+        .iter()
+        .map(|arg| shell_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let write_prompt = format!(
+        "printf '%s' {} > {}",
+        shell_quote(&config.prompt),
+        shell_quote(&prompt_file)
+    );
 
     // Build the test command
-    let test_cmd = if config.test_args.is_empty() {
-        config.test_command.clone()
-    } else {
-        format!("{} {}", config.test_command, config.test_args.join(" "))
+    let test_cmd = shell_words(&config.test_command, &config.test_args);
+
+    // Harnesses with a build step only run the tests once the build succeeds
+    let (check_cmd, build_step) = match &config.build_command {
+        Some((program, args)) => {
+            let build_cmd = shell_words(program, args);
+            let build_step = format!(
+                r#"echo "=== ANODE-EVAL Build ==="
+echo {announce}
+if ! {build_cmd} > {results}/build_output.log 2>&1; then
+    echo "BUILD_OUTPUT_START"
+    cat {results}/build_output.log
+    echo "BUILD_OUTPUT_END"
+    echo "ANODE_BUILD_FAILED"
+    exit 0
+fi"#,
+                announce = shell_quote(&format!("Running: {}", build_cmd)),
+            );
+            (format!("{{ {} && {}; }}", build_cmd, test_cmd), build_step)
+        }
+        None => (test_cmd.clone(), "echo 'No build step'".to_string()),
     };
 
+    let announce_test = shell_quote(&format!("Running: {}", test_cmd));
+
+    // Remove any results left by the ralph loop's test runs, and print the
+    // ones the final run writes
+    let mut clear_results = format!("rm -f {}", results_file);
+    let mut print_results = print_file(&results_file, "ANODE_RESULTS");
+    if let Some(path) = &config.test_report {
+        let path = shell_quote(&path.to_string_lossy());
+        clear_results.push_str(&format!(" {}", path));
+        print_results.push_str(&format!("\n{}", print_file(&path, "TEST_REPORT")));
+    }
+
     // Build git clone command if repo is specified
     let git_clone_cmd = if let Some(ref repo) = config.git_repo {
-        format!("git clone {} /workspace", repo)
+        let checkout = match &repo.git_ref {
+            Some(git_ref) => format!(
+                "git -C /tmp/anode-repo checkout --quiet {}",
+                shell_quote(git_ref)
+            ),
+            None => "true".to_string(),
+        };
+        let subdir = repo
+            .subdir
+            .as_ref()
+            .map_or(".".to_string(), |s| s.to_string_lossy().into_owned());
+        format!(
+            "git clone --quiet {} /tmp/anode-repo\n{}\ntar -C {} --exclude=.git -cf - . | tar -C {} -xf -",
+            shell_quote(&repo.url),
+            checkout,
+            shell_quote(&format!("/tmp/anode-repo/{}", subdir)),
+            workspace
+        )
     } else {
         "echo 'No git repo specified'".to_string()
     };
@@ -221,7 +442,7 @@ fn build_entrypoint_script(config: &AgentPodConfig) -> String {
         config.setup_commands.join("\n")
     };
 
-    format!(
+    Ok(format!(
         r#"#!/bin/bash
 set -e
 
@@ -229,16 +450,16 @@ echo "=== ANODE-EVAL Agent Runner ==="
 echo "Run ID: $ANODE_RUN_ID"
 echo "Agent: $ANODE_AGENT_TOOL"
 echo "Model: $ANODE_MODEL"
-echo "Iterations: $ANODE_ITERATIONS"
+echo "Max turns per invocation: $ANODE_ITERATIONS"
 echo "Timeout: $ANODE_TIMEOUT_HOURS hours"
 echo ""
 
 # Create status file
-echo "starting" > /results/status
+echo "starting" > {results}/status
 
 # Install agent CLI
 echo "Installing agent CLI..."
-{install_cmd} || {{ echo "failed" > /results/status; exit 1; }}
+{install_cmd} || {{ echo "failed" > {results}/status; exit 1; }}
 echo "Agent CLI installed successfully"
 
 # Clone repo if specified
@@ -246,63 +467,110 @@ echo "Setting up workspace..."
 {git_clone_cmd}
 
 # Setup workspace
-cd /workspace
+cd {workspace}
 
 # Run setup commands
 echo "Running setup commands..."
 {setup_cmds}
 
+# Write the prompt for agents that read it from a file
+{write_prompt}
+
 # Create a marker file to track agent activity
-touch /results/heartbeat
+touch {results}/heartbeat
 
 # Run the agent in background while monitoring
 echo "Starting agent..."
-echo "running" > /results/status
+echo "running" > {results}/status
 
 # Start heartbeat monitor in background
-(while true; do touch /results/heartbeat; sleep 30; done) &
+(while true; do touch {results}/heartbeat; sleep 30; done) &
 HEARTBEAT_PID=$!
 
-# Run the agent
-{run_command} 2>&1 | tee /results/agent_output.log
-AGENT_EXIT_CODE=${{PIPESTATUS[0]}}
+# Run the agent. Ralph-loop agents are re-invoked on the same workspace
+# until the tests pass; single-shot agents run exactly once.
+ANODE_ITERATIONS_USED=0
+for ANODE_ITERATION in $(seq 1 {max_invocations}); do
+    ANODE_ITERATIONS_USED=$ANODE_ITERATION
+    echo "=== Agent iteration $ANODE_ITERATION of {max_invocations} ==="
+    {run_command} 2>&1 | tee -a {results}/agent_output.log
+    AGENT_EXIT_CODE=${{PIPESTATUS[0]}}
+    if [ $ANODE_ITERATION -lt {max_invocations} ] && {check_cmd} > {results}/iteration_test_output.log 2>&1; then
+        echo "Tests passed after iteration $ANODE_ITERATION"
+        break
+    fi
+done
+echo "ANODE_ITERATIONS_USED=$ANODE_ITERATIONS_USED"
 
 # Stop heartbeat
 kill $HEARTBEAT_PID 2>/dev/null || true
 
 if [ $AGENT_EXIT_CODE -eq 0 ]; then
-    echo "agent_completed" > /results/status
+    echo "agent_completed" > {results}/status
     echo "Agent completed successfully"
 else
-    echo "agent_failed" > /results/status
+    echo "agent_failed" > {results}/status
     echo "Agent failed with exit code $AGENT_EXIT_CODE"
 fi
 
 # Store exit code
-echo $AGENT_EXIT_CODE > /results/agent_exit_code
+echo $AGENT_EXIT_CODE > {results}/agent_exit_code
 
 echo "=== Agent run complete ==="
 
+# Build the project if the harness has a build step
+echo ""
+{build_step}
+
 # Run eval tests
 echo ""
 echo "=== ANODE-EVAL Test Runner ==="
-echo "Running: {test_cmd}"
+echo {announce_test}
+ANODE_TEST_STARTED=$(date +%s%N)
+{clear_results}
 echo "TEST_OUTPUT_START"
-{test_cmd} 2>&1 || true
+ANODE_TEST_EXIT_CODE=0
+{test_cmd} 2>&1 || ANODE_TEST_EXIT_CODE=$?
 echo "TEST_OUTPUT_END"
+echo "ANODE_TEST_EXIT_CODE=$ANODE_TEST_EXIT_CODE"
+echo "ANODE_TEST_DURATION_MS=$(( ($(date +%s%N) - ANODE_TEST_STARTED) / 1000000 ))"
+{print_results}
 echo "=== Test run complete ==="
 "#
+    ))
+}
+
+/// Shell commands that print a file, if it exists, between `{marker}_START`
+/// and `{marker}_END` lines
+fn print_file(path: &str, marker: &str) -> String {
+    format!(
+        r#"if [ -f {path} ]; then
+    echo "{marker}_START"
+    cat {path}
+    echo ""
+    echo "{marker}_END"
+fi"#
     )
 }
 
+/// Join a program and its arguments into a shell command line
+fn shell_words(program: &str, args: &[String]) -> String {
+    std::iter::once(program)
+        .chain(args.iter().map(String::as_str))
+        .map(shell_quote)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::agents::presets;
+    use crate::agents::{presets, CustomAgentConfig, ModelVersion};
 
-    #[test]
-    fn test_pod_name_generation() {
-        let config = AgentPodConfig {
+    /// Pod config for a Claude Code agent running `cargo test`, for tests to
+    /// override fields of
+    fn test_pod_config() -> AgentPodConfig {
+        AgentPodConfig {
             agent: presets::claude_opus_45(),
             prompt: "Test prompt".to_string(),
             eval_path: "/evals/test".to_string(),
@@ -310,37 +578,74 @@ mod tests {
             namespace: "default".to_string(),
             timeout_hours: 6,
             api_keys: BTreeMap::new(),
+            env: BTreeMap::new(),
             test_command: "cargo".to_string(),
             test_args: vec!["test".to_string()],
+            build_command: None,
+            test_report: None,
             git_repo: None,
             setup_commands: vec![],
-        };
+            workspace_config_map: None,
+        }
+    }
+
+    #[test]
+    fn test_pod_name_generation() {
+        let config = test_pod_config();
 
         let pod_name = config.pod_name();
         assert!(pod_name.starts_with("anode-eval-"));
         assert!(pod_name.contains("12345678"));
     }
 
+    #[test]
+    fn test_agent_env_fits_pod_name_and_label() {
+        let mut agent = presets::claude_sonnet_4().with_ralph_loop(3, 10);
+        agent.env = BTreeMap::from([
+            ("RUST_LOG".to_string(), "debug".to_string()),
+            (
+                "API_BASE_URL".to_string(),
+                "https://proxy.internal.example.com/v1".to_string(),
+            ),
+        ]);
+        let config = AgentPodConfig {
+            agent,
+            ..test_pod_config()
+        };
+
+        let pod = build_agent_pod(&config).unwrap();
+        let pod_name = pod.metadata.name.unwrap();
+        assert!(pod_name.len() <= 63, "{}", pod_name);
+        assert!(!pod_name.contains("proxy"));
+        let label = &pod.metadata.labels.unwrap()["agent"];
+        assert!(label.len() <= 63, "{}", label);
+    }
+
+    #[test]
+    fn test_kube_name() {
+        assert_eq!(
+            kube_name("claude_code-qwen2.5-coder:7b", 63),
+            "claude-code-qwen2-5-coder-7b"
+        );
+        assert_eq!(kube_name("My Agent/ollama", 63), "my-agent-ollama");
+        // Truncation doesn't leave a trailing `-`
+        assert_eq!(kube_name("aider-gpt-5", 6), "aider");
+        assert_eq!(kube_name(&"x".repeat(100), 63).len(), 63);
+    }
+
     #[test]
     fn test_build_agent_pod() {
         let mut api_keys = BTreeMap::new();
         api_keys.insert("ANTHROPIC_API_KEY".to_string(), "test-key".to_string());
 
-        let config = AgentPodConfig {
-            agent: presets::claude_opus_45(),
+        let mut config = AgentPodConfig {
             prompt: "Write a hello world".to_string(),
-            eval_path: "/evals/hello".to_string(),
-            run_id: "12345678-abcd-1234-abcd-123456789abc".to_string(),
-            namespace: "default".to_string(),
-            timeout_hours: 6,
             api_keys,
-            test_command: "cargo".to_string(),
-            test_args: vec!["test".to_string()],
-            git_repo: None,
-            setup_commands: vec![],
+            env: BTreeMap::from([("PORT".to_string(), "8080".to_string())]),
+            ..test_pod_config()
         };
 
-        let pod = build_agent_pod(&config);
+        let pod = build_agent_pod(&config).unwrap();
 
         assert!(pod.metadata.name.is_some());
         assert_eq!(pod.metadata.namespace, Some("default".to_string()));
@@ -349,5 +654,181 @@ mod tests {
         let spec = pod.spec.unwrap();
         assert_eq!(spec.containers.len(), 1);
         assert_eq!(spec.active_deadline_seconds, Some(21600)); // 6 hours
+        assert!(spec.init_containers.is_none());
+
+        let env = spec.containers[0].env.as_ref().unwrap();
+        let port = env.iter().find(|e| e.name == "PORT").unwrap();
+        assert_eq!(port.value.as_deref(), Some("8080"));
+
+        // `env` can't override an API key
+        config
+            .env
+            .insert("ANTHROPIC_API_KEY".to_string(), "other".to_string());
+        assert!(build_agent_pod(&config).is_err());
+    }
+
+    #[test]
+    fn test_build_agent_pod_with_workspace_archive() {
+        let config = AgentPodConfig {
+            prompt: "Write a hello world".to_string(),
+            workspace_config_map: Some("anode-eval-workspace-12345678".to_string()),
+            ..test_pod_config()
+        };
+
+        let spec = build_agent_pod(&config).unwrap().spec.unwrap();
+        let init = &spec.init_containers.unwrap()[0];
+        assert_eq!(
+            init.args.as_ref().unwrap()[0],
+            "if [ -f /workspace-archive/workspace.tar.gz ]; then tar -xzf /workspace-archive/workspace.tar.gz -C /workspace; fi"
+        );
+        let archive_volume = spec
+            .volumes
+            .unwrap()
+            .into_iter()
+            .find(|v| v.name == "workspace-archive")
+            .unwrap();
+        assert_eq!(
+            archive_volume.config_map.unwrap().name.as_deref(),
+            Some("anode-eval-workspace-12345678")
+        );
+    }
+
+    #[test]
+    fn test_scripts_under_another_root() {
+        let config = AgentPodConfig {
+            agent: AgentConfig::fake("fixtures", ModelVersion::new("claude_sonnet_4")),
+            ..test_pod_config()
+        };
+        let root = Path::new("/tmp/pod");
+
+        let init = build_init_script(&config, root);
+        assert_eq!(
+            init,
+            "if [ -f /tmp/pod/workspace-archive/workspace.tar.gz ]; then tar -xzf /tmp/pod/workspace-archive/workspace.tar.gz -C /tmp/pod/workspace; fi\n\
+             tar -xzf /tmp/pod/workspace-archive/fixtures.tar.gz -C /tmp/pod/agent-fixtures"
+        );
+        let script = build_entrypoint_script(&config, root).unwrap();
+        assert!(script.contains("cd /tmp/pod/workspace\n"));
+        assert!(script.contains("fixtures=/tmp/pod/agent-fixtures"));
+        assert!(script.contains("rm -f /tmp/pod/results/test-results.json\n"));
+        assert!(!script.contains(" /results"));
+        let env = build_container_env(&config, root).unwrap();
+        assert!(env.contains(&(
+            "ANODE_RESULTS_FILE".to_string(),
+            "/tmp/pod/results/test-results.json".to_string()
+        )));
+    }
+
+    #[test]
+    fn test_entrypoint_ralph_loop() {
+        let config = AgentPodConfig {
+            agent: presets::claude_opus_45().with_ralph_loop(4, 25),
+            prompt: "Fix the tests".to_string(),
+            ..test_pod_config()
+        };
+
+        let script = build_entrypoint_script(&config, Path::new("/")).unwrap();
+        assert!(script.contains("for ANODE_ITERATION in $(seq 1 4); do"));
+        assert!(script.contains("--max-turns 25"));
+        assert!(script.contains("ANODE_ITERATION -lt 4 ] && cargo test"));
+        assert!(script.contains("echo \"ANODE_ITERATIONS_USED=$ANODE_ITERATIONS_USED\""));
+    }
+
+    #[test]
+    fn test_entrypoint_uses_adapter_invocation() {
+        let config = AgentPodConfig {
+            prompt: "Don't break".to_string(),
+            ..test_pod_config()
+        };
+
+        let script = build_entrypoint_script(&config, Path::new("/")).unwrap();
+        assert!(script.contains("npm install -g @anthropic-ai/claude-code"));
+        assert!(script.contains(
+            "claude --model claude-opus-4-5-20251101 --max-turns 10 --output-format stream-json --verbose --dangerously-skip-permissions -p 'Don'\\''t break'"
+        ));
+    }
+
+    #[test]
+    fn test_entrypoint_build_step() {
+        let config = AgentPodConfig {
+            prompt: "Build it".to_string(),
+            test_command: "bash".to_string(),
+            test_args: vec![
+                "-c".to_string(),
+                "cargo test -- --test-threads=1".to_string(),
+            ],
+            build_command: Some((
+                "timeout".to_string(),
+                vec![
+                    "600".to_string(),
+                    "bash".to_string(),
+                    "-c".to_string(),
+                    "cargo build --release".to_string(),
+                ],
+            )),
+            test_report: Some(PathBuf::from("target/junit.xml")),
+            ..test_pod_config()
+        };
+
+        let script = build_entrypoint_script(&config, Path::new("/")).unwrap();
+        assert!(script.contains(
+            "if ! timeout 600 bash -c 'cargo build --release' > /results/build_output.log 2>&1; then"
+        ));
+        assert!(script.contains("echo \"ANODE_BUILD_FAILED\""));
+        assert!(script
+            .contains("bash -c 'cargo test -- --test-threads=1' 2>&1 || ANODE_TEST_EXIT_CODE=$?"));
+        assert!(script.contains(
+            "rm -f /results/test-results.json target/junit.xml\necho \"TEST_OUTPUT_START\""
+        ));
+        assert!(script.contains("    cat /results/test-results.json\n"));
+        assert!(script.contains("    cat target/junit.xml\n"));
+        assert!(script.contains(
+            "echo \"ANODE_TEST_DURATION_MS=$(( ($(date +%s%N) - ANODE_TEST_STARTED) / 1000000 ))\""
+        ));
+    }
+
+    #[test]
+    fn test_entrypoint_custom_agent() {
+        let custom = CustomAgentConfig {
+            name: "aider".to_string(),
+            install: Some("pip install aider-chat".to_string()),
+            command: "aider --model {model} --message-file {prompt_file} --yes".to_string(),
+            env: vec!["OPENAI_API_KEY".to_string()],
+            output_parser: None,
+        };
+        let config = AgentPodConfig {
+            agent: AgentConfig::custom(custom, ModelVersion::new("gpt_5")),
+            prompt: "Fix the tests".to_string(),
+            ..test_pod_config()
+        };
+
+        let script = build_entrypoint_script(&config, Path::new("/")).unwrap();
+        assert!(script.contains("pip install aider-chat ||"));
+        assert!(script.contains("printf '%s' 'Fix the tests' > /results/prompt.txt"));
+        assert!(script.contains(
+            "bash -c 'aider --model gpt-5 --message-file /results/prompt.txt --yes' 2>&1"
+        ));
+        assert!(config.pod_name().starts_with("anode-eval-aider-gpt-5-"));
+    }
+
+    #[test]
+    fn test_entrypoint_git_repo() {
+        let config = AgentPodConfig {
+            prompt: "Fix the tests".to_string(),
+            eval_path: "https://example.com/evals.git".to_string(),
+            git_repo: Some(GitRepoConfig {
+                url: "https://example.com/evals.git".to_string(),
+                git_ref: Some("v1.2".to_string()),
+                subdir: Some("rust/hello".into()),
+            }),
+            ..test_pod_config()
+        };
+
+        let script = build_entrypoint_script(&config, Path::new("/")).unwrap();
+        assert!(script.contains("git clone --quiet https://example.com/evals.git /tmp/anode-repo"));
+        assert!(script.contains("git -C /tmp/anode-repo checkout --quiet v1.2"));
+        assert!(script.contains(
+            "tar -C /tmp/anode-repo/rust/hello --exclude=.git -cf - . | tar -C /workspace -xf -"
+        ));
     }
 }
@@ -0,0 +1,249 @@
+//! Delivery of the eval's starter files into the agent pod
+//!
+//! The contents of `eval_path` are packed into a gzipped tarball and stored in
+//! a ConfigMap, along with a tarball of a fake agent's fixtures. An init
+//! container unpacks them into the `/workspace` and `/agent-fixtures` volumes before
+//! the agent container starts.
+
+use crate::eval::SKIPPED_DIRS;
+use anyhow::{Context, Result};
+use k8s_openapi::api::core::v1::ConfigMap;
+use k8s_openapi::ByteString;
+use kube::api::ObjectMeta;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+
+/// Key of the tarball in the workspace ConfigMap
+pub const WORKSPACE_ARCHIVE_KEY: &str = "workspace.tar.gz";
+
+/// Key of the fake agent's fixtures tarball in the workspace ConfigMap
+pub const FIXTURES_ARCHIVE_KEY: &str = "fixtures.tar.gz";
+
+/// Kubernetes rejects ConfigMaps over 1MiB (including metadata)
+pub const MAX_WORKSPACE_ARCHIVE_BYTES: usize = 1000 * 1024;
+
+/// Name of the ConfigMap holding the workspace for a run
+pub fn workspace_config_map_name(run_id: &str) -> String {
+    format!("anode-eval-workspace-{}", run_id)
+}
+
+/// Pack the files a run ships to its pod, keyed by their ConfigMap key:
+/// `eval_path` and a fake agent's `fixtures`, if any
+pub async fn archive_run_files(
+    eval_path: Option<&Path>,
+    fixtures: Option<&Path>,
+) -> Result<BTreeMap<String, Vec<u8>>> {
+    let mut archives = BTreeMap::new();
+    if let Some(eval_path) = eval_path {
+        archives.insert(
+            WORKSPACE_ARCHIVE_KEY.to_string(),
+            archive_workspace(eval_path).await?,
+        );
+    }
+    if let Some(fixtures) = fixtures {
+        let archive = archive_workspace(fixtures)
+            .await
+            .context("Failed to archive the fake agent's fixtures")?;
+        archives.insert(FIXTURES_ARCHIVE_KEY.to_string(), archive);
+    }
+
+    let size: usize = archives.values().map(Vec::len).sum();
+    if size > MAX_WORKSPACE_ARCHIVE_BYTES {
+        anyhow::bail!(
+            "The workspace and fixtures are {} bytes compressed, over the {} byte ConfigMap limit",
+            size,
+            MAX_WORKSPACE_ARCHIVE_BYTES
+        );
+    }
+    Ok(archives)
+}
+
+/// Pack the contents of `eval_path` into a gzipped tarball, skipping build
+/// output and VCS directories
+pub async fn archive_workspace(eval_path: &Path) -> Result<Vec<u8>> {
+    if !eval_path.is_dir() {
+        anyhow::bail!("{:?} is not a directory", eval_path);
+    }
+
+    // tar's --exclude would also drop files with those names, so the entries
+    // are listed here, skipping directories only as the local runner does
+    let mut entries = Vec::new();
+    list_entries(eval_path, Path::new("."), &mut entries)?;
+    let mut file_list = Vec::new();
+    for entry in &entries {
+        file_list.extend_from_slice(entry.as_os_str().as_encoded_bytes());
+        file_list.push(0);
+    }
+
+    let mut child = tokio::process::Command::new("tar")
+        .arg("-czf")
+        .arg("-")
+        .arg("-C")
+        .arg(eval_path)
+        .args(["--null", "--no-recursion", "-T", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run tar")?;
+    // Write the list while tar runs; dropping stdin ends it
+    let mut stdin = child.stdin.take().expect("tar stdin is piped");
+    let write_list = async move { stdin.write_all(&file_list).await };
+    let (written, output) = tokio::join!(write_list, child.wait_with_output());
+    let output = output.context("Failed to run tar")?;
+    written.context("Failed to pass the file list to tar")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "tar failed for {:?}: {}",
+            eval_path,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    if output.stdout.len() > MAX_WORKSPACE_ARCHIVE_BYTES {
+        anyhow::bail!(
+            "{:?} is {} bytes compressed, over the {} byte ConfigMap limit",
+            eval_path,
+            output.stdout.len(),
+            MAX_WORKSPACE_ARCHIVE_BYTES
+        );
+    }
+
+    Ok(output.stdout)
+}
+
+/// List the entries under `dir` (`relative` to the eval path), skipping
+/// [`SKIPPED_DIRS`] directories
+fn list_entries(dir: &Path, relative: &Path, entries: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = relative.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            if SKIPPED_DIRS.iter().any(|d| entry.file_name() == *d) {
+                continue;
+            }
+            entries.push(path.clone());
+            list_entries(&entry.path(), &path, entries)?;
+        } else {
+            entries.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Build the ConfigMap carrying a run's tarballs, keyed as by
+/// [`archive_run_files`]
+pub fn build_workspace_config_map(
+    run_id: &str,
+    namespace: &str,
+    archives: BTreeMap<String, Vec<u8>>,
+) -> ConfigMap {
+    let mut labels = BTreeMap::new();
+    labels.insert("app".to_string(), "anode-eval".to_string());
+    labels.insert("run-id".to_string(), run_id.to_string());
+
+    ConfigMap {
+        metadata: ObjectMeta {
+            name: Some(workspace_config_map_name(run_id)),
+            namespace: Some(namespace.to_string()),
+            labels: Some(labels),
+            ..Default::default()
+        },
+        binary_data: Some(
+            archives
+                .into_iter()
+                .map(|(key, archive)| (key, ByteString(archive)))
+                .collect(),
+        ),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    /// Paths in a gzipped tarball
+    fn list_archive(archive: &[u8]) -> String {
+        let mut tar = Command::new("tar")
+            .arg("-tzf")
+            .arg("-")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        std::io::Write::write_all(tar.stdin.as_mut().unwrap(), archive).unwrap();
+        String::from_utf8(tar.wait_with_output().unwrap().stdout).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_archive_workspace_skips_build_output() {
+        let src = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(src.path().join("src")).unwrap();
+        std::fs::create_dir_all(src.path().join("target/debug")).unwrap();
+        std::fs::write(src.path().join("Cargo.toml"), "[package]").unwrap();
+        std::fs::write(src.path().join("src/lib.rs"), "").unwrap();
+        std::fs::write(src.path().join("target/debug/big"), "x").unwrap();
+
+        let archive = archive_workspace(src.path()).await.unwrap();
+
+        let listing = list_archive(&archive);
+
+        assert!(listing.contains("./src/lib.rs"));
+        assert!(listing.contains("./Cargo.toml"));
+        assert!(!listing.contains("target"));
+    }
+
+    #[tokio::test]
+    async fn test_archive_workspace_keeps_files_named_like_skipped_dirs() {
+        let src = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(src.path().join("docs")).unwrap();
+        std::fs::write(src.path().join("docs/target"), "x").unwrap();
+
+        let archive = archive_workspace(src.path()).await.unwrap();
+
+        let listing = list_archive(&archive);
+        assert!(listing.contains("./docs/target"), "{}", listing);
+    }
+
+    #[tokio::test]
+    async fn test_archive_run_files() {
+        let src = tempfile::tempdir().unwrap();
+        let fixtures = tempfile::tempdir().unwrap();
+        std::fs::write(src.path().join("Cargo.toml"), "[package]").unwrap();
+        std::fs::write(fixtures.path().join("usage.json"), "{}").unwrap();
+
+        let archives = archive_run_files(Some(src.path()), Some(fixtures.path()))
+            .await
+            .unwrap();
+        assert!(list_archive(&archives[WORKSPACE_ARCHIVE_KEY]).contains("./Cargo.toml"));
+        assert!(list_archive(&archives[FIXTURES_ARCHIVE_KEY]).contains("./usage.json"));
+
+        let archives = archive_run_files(None, None).await.unwrap();
+        assert!(archives.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_archive_missing_eval_path() {
+        let err = archive_workspace(Path::new("/nonexistent/eval"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not a directory"));
+    }
+
+    #[test]
+    fn test_build_workspace_config_map() {
+        let archives = BTreeMap::from([(WORKSPACE_ARCHIVE_KEY.to_string(), vec![1, 2, 3])]);
+        let config_map = build_workspace_config_map("run-1", "default", archives);
+        assert_eq!(
+            config_map.metadata.name.as_deref(),
+            Some("anode-eval-workspace-run-1")
+        );
+        let data = config_map.binary_data.unwrap();
+        assert_eq!(data[WORKSPACE_ARCHIVE_KEY].0, vec![1, 2, 3]);
+    }
+}
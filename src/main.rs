@@ -9,6 +9,7 @@ use anyhow::Result;
 use clap::Parser;
 use cli::{Args, Command, EvalConfig};
 use eval::{EvalRunner, LocalEvalRunner};
+use std::path::Path;
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
 
@@ -23,15 +24,27 @@ async fn main() -> Result<()> {
         Level::INFO
     };
 
-    let _subscriber = FmtSubscriber::builder()
+    FmtSubscriber::builder()
         .with_max_level(log_level)
         .with_target(false)
         .init();
 
+    let mut models = agents::ModelRegistry::with_builtin();
+    if let Some(path) = &args.models {
+        models.load_file(path)?;
+    }
+    agents::init_model_registry(models)?;
+
     match args.command {
         Command::Run(run_args) => {
             run_evaluation(&args.namespace, run_args).await?;
         }
+        Command::Validate(validate_args) => {
+            validate_config(validate_args)?;
+        }
+        Command::Schema(schema_args) => {
+            print_config_schema(schema_args)?;
+        }
         Command::Status(status_args) => {
             check_status(&args.namespace, status_args).await?;
         }
@@ -50,6 +63,9 @@ async fn main() -> Result<()> {
         Command::Ui(ui_args) => {
             start_ui_server(ui_args).await?;
         }
+        Command::PodScripts(pod_scripts_args) => {
+            write_pod_scripts(pod_scripts_args).await?;
+        }
     }
 
     Ok(())
@@ -58,36 +74,49 @@ async fn main() -> Result<()> {
 async fn run_evaluation(namespace: &str, args: cli::RunArgs) -> Result<()> {
     info!("Loading evaluation config from {:?}", args.config);
 
-    let config = EvalConfig::load(&args.config)?;
+    let filter = cli::RunFilter::new(args.prompts.clone(), args.agents.clone(), args.tags.clone())?;
+    let config = EvalConfig::load(&args.config, false)?
+        .with_overrides(&args.overrides)?
+        .with_filter(filter);
+    if !config.filter.is_empty() && config.combinations().is_empty() {
+        anyhow::bail!("No (prompt, agent) combinations match {}", config.filter);
+    }
+    // Only the agents that actually run need their API keys
+    if !args.dry_run && !args.skip_agent {
+        config.check_api_keys()?;
+    }
+    let output_dir = args
+        .output
+        .clone()
+        .unwrap_or_else(|| config.settings.output_dir.clone());
 
     if args.dry_run {
         println!("Dry run mode - no pods will be created");
         println!("\nConfiguration:");
         println!("  Name: {}", config.name);
-        println!("  Prompts: {}", config.prompts.len());
-        println!("  Agents: {}", config.agents.len());
-        println!(
-            "  Total combinations: {}",
-            config.prompts.len() * config.agents.len()
-        );
+        if !config.filter.is_empty() {
+            println!("  Filter: {}", config.filter);
+        }
+        println!("  Prompts: {}", config.selected_prompts().count());
+        println!("  Agents: {}", config.selected_agents().count());
+        println!("  Total combinations: {}", config.combinations().len());
+        println!("  Total runs: {}", config.scheduled_runs().len());
+        println!("  Output directory: {:?}", output_dir);
         println!("\nPrompts:");
-        for prompt in &config.prompts {
-            println!(
-                "  - {}: {}",
-                prompt.id,
-                &prompt.prompt[..prompt.prompt.len().min(50)]
-            );
+        for prompt in config.selected_prompts() {
+            let summary: String = prompt.prompt.chars().take(50).collect();
+            println!("  - {}: {}", prompt.id, summary);
         }
         println!("\nAgents:");
-        for agent in &config.agents {
-            println!("  - {} ({})", agent.tool, agent.model);
+        for agent in config.selected_agents() {
+            println!("  - {} ({})", agent.tool_name(), agent.model);
         }
         return Ok(());
     }
 
     // Use local runner if --local flag is set
     if args.local {
-        return run_local_evaluation(config, args).await;
+        return run_local_evaluation(config, args, &output_dir).await;
     }
 
     let runner = EvalRunner::new(config, namespace).await?;
@@ -95,32 +124,59 @@ async fn run_evaluation(namespace: &str, args: cli::RunArgs) -> Result<()> {
 
     print_results(&results);
 
-    // Save results
-    let output_dir = args
-        .output
-        .unwrap_or_else(|| std::path::PathBuf::from(&results.eval_id));
-    runner.save_results(&output_dir).await?;
+    let eval_dir = runner.save_results(&output_dir).await?;
 
-    println!("\nResults saved to: {:?}", output_dir);
+    println!("\nResults saved to: {:?}", eval_dir);
 
     Ok(())
 }
 
-async fn run_local_evaluation(config: EvalConfig, args: cli::RunArgs) -> Result<()> {
-    println!("\n*** LOCAL MODE - Running tests without Kubernetes ***\n");
+fn validate_config(args: cli::ValidateArgs) -> Result<()> {
+    let config = EvalConfig::load(&args.config, !args.skip_api_keys)?;
 
-    let runner = LocalEvalRunner::new(config);
-    let results = runner.run_local_tests().await?;
+    println!(
+        "{:?} is valid: {} prompts, {} agents",
+        args.config,
+        config.prompts.len(),
+        config.agents.len()
+    );
+
+    Ok(())
+}
+
+fn print_config_schema(args: cli::SchemaArgs) -> Result<()> {
+    let schema = serde_json::to_string_pretty(&cli::config_schema())?;
+
+    match args.output {
+        Some(path) => {
+            std::fs::write(&path, schema + "\n")?;
+            println!("Schema written to: {:?}", path);
+        }
+        None => println!("{}", schema),
+    }
+
+    Ok(())
+}
+
+async fn run_local_evaluation(
+    config: EvalConfig,
+    args: cli::RunArgs,
+    output_dir: &Path,
+) -> Result<()> {
+    if args.skip_agent {
+        println!("\n*** LOCAL MODE - Running tests only, agents skipped ***\n");
+    } else {
+        println!("\n*** LOCAL MODE - Running agents without Kubernetes ***\n");
+    }
+
+    let runner = LocalEvalRunner::new(config)?.with_skip_agent(args.skip_agent);
+    let results = runner.run(args.timeout_hours).await?;
 
     print_results(&results);
 
-    // Save results
-    let output_dir = args
-        .output
-        .unwrap_or_else(|| std::path::PathBuf::from(&results.eval_id));
-    runner.save_results(&output_dir).await?;
+    let eval_dir = runner.save_results(output_dir).await?;
 
-    println!("\nResults saved to: {:?}", output_dir);
+    println!("\nResults saved to: {:?}", eval_dir);
 
     Ok(())
 }
@@ -149,6 +205,14 @@ fn print_results(results: &eval::EvaluationResults) {
             score.passed_tests,
             score.total_tests
         );
+        for (prompt_id, stats) in &score.prompt_scores {
+            if stats.samples > 1 {
+                println!(
+                    "      {}: {:.2}% +/- {:.2} (min {:.2}%, max {:.2}%, {} samples)",
+                    prompt_id, stats.mean, stats.std_dev, stats.min, stats.max, stats.samples
+                );
+            }
+        }
     }
 }
 
@@ -248,3 +312,12 @@ async fn start_ui_server(args: cli::UiArgs) -> Result<()> {
 
     Ok(())
 }
+
+async fn write_pod_scripts(args: cli::PodScriptsArgs) -> Result<()> {
+    let filter = cli::RunFilter::new(args.prompts, args.agents, vec![])?;
+    let config = EvalConfig::load(&args.config, false)?.with_filter(filter);
+    eval::write_pod_scripts(&config, &args.root).await?;
+
+    println!("Run the pod with: bash {}", args.root.join("pod.sh").display());
+    Ok(())
+}
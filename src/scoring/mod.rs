@@ -38,6 +38,7 @@ impl DetailedScore {
 }
 
 /// Calculate detailed scores for all agents in the results
+#[allow(dead_code)]
 pub fn calculate_detailed_scores(results: &EvaluationResults) -> Vec<(String, DetailedScore)> {
     results
         .agent_scores
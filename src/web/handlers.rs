@@ -9,7 +9,8 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-use super::state::{AppState, SessionInfo, SessionStatus, StoredEvalResult};
+use super::state::StoredEvalResult;
+use super::{AppState, SessionInfo, SessionStatus};
 use crate::eval::EvaluationResults;
 
 /// Query parameters for listing sessions
@@ -169,7 +170,7 @@ pub async fn api_list_sessions(
     let failed = sessions.iter().filter(|s| s.status == SessionStatus::Failed).count();
 
     // Sort by started_at descending
-    sessions.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.started_at));
 
     Json(SessionListResponse {
         sessions,
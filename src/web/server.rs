@@ -11,7 +11,7 @@ use tower_http::cors::{Any, CorsLayer};
 use tracing::info;
 
 use super::handlers;
-use super::state::AppState;
+use super::AppState;
 
 /// Start the web UI server
 pub async fn start_server(port: u16, results_dir: PathBuf) -> anyhow::Result<()> {
@@ -1,6 +1,6 @@
 //! Shared state for the web UI
 
-use crate::eval::{EvaluationResults, RunStatus};
+use crate::eval::{EvaluationResults, ResultsLayout, RunStatus};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -30,7 +30,9 @@ impl From<RunStatus> for SessionStatus {
             RunStatus::Pending => SessionStatus::Queued,
             RunStatus::Running => SessionStatus::Running,
             RunStatus::Completed => SessionStatus::Completed,
-            RunStatus::Failed | RunStatus::Timeout => SessionStatus::Failed,
+            RunStatus::Failed | RunStatus::BuildFailed | RunStatus::Timeout => {
+                SessionStatus::Failed
+            }
             RunStatus::Cancelled => SessionStatus::Cancelled,
         }
     }
@@ -72,6 +74,7 @@ pub struct SessionInfo {
 }
 
 impl SessionInfo {
+    #[allow(dead_code)]
     pub fn new(
         session_id: &str,
         eval_id: &str,
@@ -99,6 +102,7 @@ impl SessionInfo {
         }
     }
 
+    #[allow(dead_code)]
     pub fn add_log(&mut self, line: &str) {
         self.recent_logs.push(line.to_string());
         // Keep only last 100 lines
@@ -107,11 +111,13 @@ impl SessionInfo {
         }
     }
 
+    #[allow(dead_code)]
     pub fn set_running(&mut self) {
         self.status = SessionStatus::Running;
         self.progress_message = "Agent is working...".to_string();
     }
 
+    #[allow(dead_code)]
     pub fn set_completed(&mut self, tests_passed: u32, tests_total: u32) {
         self.status = SessionStatus::Completed;
         self.completed_at = Some(Utc::now());
@@ -120,6 +126,7 @@ impl SessionInfo {
         self.progress_message = format!("Completed: {}/{} tests passed", tests_passed, tests_total);
     }
 
+    #[allow(dead_code)]
     pub fn set_failed(&mut self, error: &str) {
         self.status = SessionStatus::Failed;
         self.completed_at = Some(Utc::now());
@@ -127,6 +134,7 @@ impl SessionInfo {
         self.progress_message = format!("Failed: {}", error);
     }
 
+    #[allow(dead_code)]
     pub fn duration_seconds(&self) -> i64 {
         let end = self.completed_at.unwrap_or_else(Utc::now);
         (end - self.started_at).num_seconds()
@@ -165,6 +173,7 @@ impl AppState {
     }
 
     /// Add or update a session
+    #[allow(dead_code)]
     pub async fn upsert_session(&self, session: SessionInfo) {
         let mut sessions = self.sessions.write().await;
         sessions.insert(session.session_id.clone(), session);
@@ -177,6 +186,7 @@ impl AppState {
     }
 
     /// Get sessions for a specific eval
+    #[allow(dead_code)]
     pub async fn get_sessions_for_eval(&self, eval_id: &str) -> Vec<SessionInfo> {
         let sessions = self.sessions.read().await;
         sessions
@@ -192,79 +202,17 @@ impl AppState {
         sessions.get(session_id).cloned()
     }
 
-    /// Load results from disk
+    /// Load results from disk, from the layout `anode-eval run` writes
     pub async fn load_results(&self) -> anyhow::Result<()> {
-        let mut stored_results = Vec::new();
-
-        if self.results_dir.exists() {
-            for entry in std::fs::read_dir(&self.results_dir)? {
-                let entry = entry?;
-                let path = entry.path();
-
-                if path.extension().map_or(false, |e| e == "json") {
-                    if let Ok(content) = std::fs::read_to_string(&path) {
-                        if let Ok(results) = serde_json::from_str::<EvaluationResults>(&content) {
-                            stored_results.push(StoredEvalResult {
-                                path: path.clone(),
-                                results,
-                                loaded_at: Utc::now(),
-                            });
-                        }
-                    }
-                }
-            }
-        }
-
-        // Also scan subdirectories (each eval creates its own dir)
-        if self.results_dir.exists() {
-            for entry in std::fs::read_dir(&self.results_dir)? {
-                let entry = entry?;
-                let path = entry.path();
-
-                if path.is_dir() {
-                    // Look for results.json in subdirectory
-                    let results_file = path.join("results.json");
-                    if results_file.exists() {
-                        if let Ok(content) = std::fs::read_to_string(&results_file) {
-                            if let Ok(results) = serde_json::from_str::<EvaluationResults>(&content)
-                            {
-                                stored_results.push(StoredEvalResult {
-                                    path: results_file,
-                                    results,
-                                    loaded_at: Utc::now(),
-                                });
-                            }
-                        }
-                    }
-
-                    // Also check for eval_id.json pattern
-                    for sub_entry in std::fs::read_dir(&path)? {
-                        let sub_entry = sub_entry?;
-                        let sub_path = sub_entry.path();
-
-                        if sub_path.extension().map_or(false, |e| e == "json") {
-                            if let Ok(content) = std::fs::read_to_string(&sub_path) {
-                                if let Ok(results) =
-                                    serde_json::from_str::<EvaluationResults>(&content)
-                                {
-                                    // Avoid duplicates
-                                    if !stored_results.iter().any(|r| r.results.eval_id == results.eval_id) {
-                                        stored_results.push(StoredEvalResult {
-                                            path: sub_path,
-                                            results,
-                                            loaded_at: Utc::now(),
-                                        });
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        // Sort by started_at descending (newest first)
-        stored_results.sort_by(|a, b| b.results.started_at.cmp(&a.results.started_at));
+        let stored_results = ResultsLayout::new(&self.results_dir)
+            .load_all()?
+            .into_iter()
+            .map(|(path, results)| StoredEvalResult {
+                path,
+                results,
+                loaded_at: Utc::now(),
+            })
+            .collect();
 
         let mut results = self.results.write().await;
         *results = stored_results;
@@ -0,0 +1,184 @@
+//! Offline integration tests for the full evaluation pipeline
+//!
+//! The built-in fake agent replays fixtures from `tests/fixtures` instead of
+//! calling a model, so config loading, the local runner, test parsing, scoring
+//! and result files are exercised without agent CLIs or API keys.
+
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Get the project root directory
+fn project_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Load an example config, pointing its prompts at the example directory and
+/// replacing its agents with a fake agent replaying `fixtures`
+fn fake_agent_config(example: &str, fixtures: &Path) -> serde_yaml::Value {
+    let example_dir = project_root().join("examples").join(example);
+    let content = std::fs::read_to_string(example_dir.join("eval-config.yaml"))
+        .expect("Failed to read example config");
+    let mut config: serde_yaml::Value =
+        serde_yaml::from_str(&content).expect("Failed to parse example config");
+
+    // The config is written elsewhere, so paths relative to it must be made
+    // absolute
+    for prompt in config["prompts"].as_sequence_mut().unwrap() {
+        prompt["eval_path"] = example_dir.to_string_lossy().into_owned().into();
+        if let Some(prompt_file) = prompt["prompt_file"].as_str() {
+            let prompt_file = example_dir.join(prompt_file);
+            prompt["prompt_file"] = prompt_file.to_string_lossy().into_owned().into();
+        }
+    }
+    config["agents"] = serde_yaml::from_str(&format!(
+        "- tool: fake\n  model: claude_sonnet_4\n  fake:\n    fixtures: {:?}\n",
+        fixtures
+    ))
+    .unwrap();
+    config["settings"]["api_keys"] = serde_yaml::from_str("env_vars: []").unwrap();
+
+    config
+}
+
+/// Run `anode-eval run --local` on a config and return the saved results
+fn run_local(config: &serde_yaml::Value) -> Value {
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let config_path = dir.path().join("eval-config.yaml");
+    let output_dir = dir.path().join("results");
+    std::fs::write(&config_path, serde_yaml::to_string(config).unwrap()).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_anode-eval"))
+        .args(["run", "--local", "--config"])
+        .arg(&config_path)
+        .arg("--output")
+        .arg(&output_dir)
+        .output()
+        .expect("Failed to run anode-eval");
+    assert!(
+        output.status.success(),
+        "anode-eval failed:\n{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let read_json = |path: PathBuf| -> Value {
+        let content = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Failed to read {:?}: {}", path, e));
+        serde_json::from_str(&content).unwrap()
+    };
+    let index = read_json(output_dir.join("index.json"));
+    let eval_id = index["evals"][0]["eval_id"]
+        .as_str()
+        .expect("No eval in index");
+    let eval_dir = output_dir.join(eval_id);
+    assert!(eval_dir.join("report.md").exists());
+    read_json(eval_dir.join("results.json"))
+}
+
+#[test]
+fn test_fake_agent_hello_world() {
+    let config = fake_agent_config(
+        "hello_world",
+        &project_root().join("tests/fixtures/hello_world"),
+    );
+    let results = run_local(&config);
+
+    let run = &results["runs"][0];
+    assert_eq!(run["status"], "completed");
+    assert_eq!(run["agent_tool"], "fake");
+    assert_eq!(run["test_results"]["total"], 8);
+    assert_eq!(run["test_results"]["passed"], 8);
+    assert_eq!(run["score"], 100.0);
+    assert_eq!(run["turns_used"], 3);
+
+    // usage.json reports no cost, so it comes from the model's prices
+    let usage = &run["token_usage"];
+    assert_eq!(usage["input_tokens"], 1200);
+    assert_eq!(usage["output_tokens"], 300);
+    let cost = usage["total_cost_usd"].as_f64().unwrap();
+    assert!((cost - 0.0081).abs() < 1e-9, "unexpected cost {}", cost);
+
+    assert_eq!(results["summary"]["overall_pass_rate"], 100.0);
+}
+
+#[test]
+fn test_fake_agent_without_edits_fails_tests() {
+    let empty_fixtures = tempfile::tempdir().unwrap();
+    let config = fake_agent_config("hello_world", empty_fixtures.path());
+    let results = run_local(&config);
+
+    let run = &results["runs"][0];
+    assert_eq!(run["test_results"]["total"], 8);
+    assert_eq!(run["test_results"]["passed"], 0);
+    assert_eq!(run["score"], 0.0);
+    assert!(run["token_usage"].is_null());
+}
+
+#[test]
+fn test_fake_agent_pod_scripts() {
+    let mut config = fake_agent_config(
+        "hello_world",
+        &project_root().join("tests/fixtures/hello_world"),
+    );
+    // Cache the build across test runs
+    let target_dir = project_root().join("target/fake-agent-pod");
+    config["prompts"][0]["env"] =
+        serde_yaml::from_str(&format!("CARGO_TARGET_DIR: {:?}\n", target_dir)).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("eval-config.yaml");
+    std::fs::write(&config_path, serde_yaml::to_string(&config).unwrap()).unwrap();
+
+    let root = dir.path().join("pod");
+    let output = Command::new(env!("CARGO_BIN_EXE_anode-eval"))
+        .args(["pod-scripts", "--config"])
+        .arg(&config_path)
+        .arg("--root")
+        .arg(&root)
+        .output()
+        .expect("Failed to run anode-eval");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // The logs go to a file: the heartbeat loop's `sleep` outlives the script
+    // and would hold a pipe open
+    let logs_path = dir.path().join("pod.log");
+    let logs = std::fs::File::create(&logs_path).unwrap();
+    let status = Command::new("bash")
+        .arg(root.join("pod.sh"))
+        .stdout(logs.try_clone().unwrap())
+        .stderr(logs)
+        .status()
+        .expect("Failed to run the pod scripts");
+    let logs = std::fs::read_to_string(&logs_path).unwrap();
+    assert!(status.success(), "{}", logs);
+
+    // The fake agent replayed its fixtures and the tests passed
+    assert!(logs.contains("\"num_turns\":3"), "{}", logs);
+    assert!(logs.contains("ANODE_ITERATIONS_USED=1"), "{}", logs);
+    assert!(logs.contains("test result: ok. 8 passed"), "{}", logs);
+    assert!(logs.contains("ANODE_TEST_EXIT_CODE=0"), "{}", logs);
+    let exit_code = std::fs::read_to_string(root.join("results/agent_exit_code")).unwrap();
+    assert_eq!(exit_code.trim(), "0");
+}
+
+#[test]
+#[ignore = "Serves crm_api on port 3000; run on its own with --ignored"]
+fn test_fake_agent_crm_api() {
+    let mut config = fake_agent_config("crm_api", &project_root().join("tests/fixtures/crm_api"));
+
+    // The conformance tests build in release mode; cache that build across runs
+    let target_dir = project_root().join("target/fake-agent-crm-api");
+    config["prompts"][0]["env"] =
+        serde_yaml::from_str(&format!("CARGO_TARGET_DIR: {:?}\n", target_dir)).unwrap();
+    let results = run_local(&config);
+
+    let run = &results["runs"][0];
+    assert_eq!(run["test_results"]["total"], 15);
+    assert_eq!(run["test_results"]["passed"], 15);
+    assert_eq!(run["turns_used"], 12);
+    assert_eq!(run["token_usage"]["total_cost_usd"], 0.42);
+}